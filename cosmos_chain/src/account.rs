@@ -3,11 +3,11 @@ use std::str::FromStr;
 use bitcoin::bip32::ExtendedPubKey;
 use hdpath::StandardHDPath;
 use ibc_proto::cosmos;
-use log::info;
+use tracing::info;
 use secp256k1::{SecretKey, PublicKey, Secp256k1};
 use utils::file::toml_file;
 
-use crate::{keyring::{CosmosKey, decode_bech32_address, EncodedPubKey, private_key_from_mnemonic, Secp256k1KeyPair}, error::Error};
+use crate::{keyring::{CosmosKey, decode_bech32_address, encode_bech32_address, address_bytes_from_public_key, EncodedPubKey, KeyType, private_key_from_mnemonic, Secp256k1KeyPair}, error::Error, config::CosmosChainConfig};
 
 #[derive(Debug, Clone)]
 pub struct Secp256k1Account {
@@ -25,13 +25,15 @@ impl Secp256k1Account {
         let encoded_pub_key: EncodedPubKey = cosmos_key.pubkey.parse()?;
         info!("{:?}", encoded_pub_key);
 
+        let key_type = KeyType::from_public_key_type_url(&encoded_pub_key.r#type);
         let mut encoded_pub_key_bytes = encoded_pub_key.key;
 
 
         let s_hd_path = StandardHDPath::from_str(hd_path)
             .map_err( |_e| Error::hd_path(hd_path.to_string()))?;
 
-        let secp256k1_key_pair = Secp256k1KeyPair::from_mnemonic(&cosmos_key.mnemonic, &s_hd_path)?;
+        let secp256k1_key_pair = Secp256k1KeyPair::from_mnemonic(&cosmos_key.mnemonic, &s_hd_path)?
+            .with_key_type(key_type);
         let derived_pub_key_bytes = secp256k1_key_pair.public_key.serialize().to_vec();
         info!("derived public key bytes: {:?}", derived_pub_key_bytes);
 
@@ -51,6 +53,71 @@ impl Secp256k1Account {
 
     }
 
+    /// Derive the account from a mnemonic along `hd_path`, without a key file,
+    /// encoding its address with the chain's bech32 `account_prefix`.
+    pub fn from_mnemonic(
+        mnemonic: &str,
+        hd_path: &str,
+        account_prefix: &str,
+        key_type: KeyType,
+    ) -> Result<Self, Error> {
+        let s_hd_path = StandardHDPath::from_str(hd_path)
+            .map_err(|_e| Error::hd_path(hd_path.to_string()))?;
+
+        let secp256k1_key_pair =
+            Secp256k1KeyPair::from_mnemonic(mnemonic, &s_hd_path)?.with_key_type(key_type);
+        let address_bytes = address_bytes_from_public_key(&secp256k1_key_pair.public_key, key_type);
+        let address = encode_bech32_address(account_prefix, &address_bytes)?;
+
+        Ok(Self {
+            key_pair: Some(secp256k1_key_pair),
+            address_bytes,
+            address,
+        })
+    }
+
+    /// Derive the account from the mnemonic held by the environment variable `env_var`,
+    /// so the mnemonic never has to be written to a config or key file.
+    pub fn from_mnemonic_env(
+        env_var: &str,
+        hd_path: &str,
+        account_prefix: &str,
+        key_type: KeyType,
+    ) -> Result<Self, Error> {
+        let mnemonic = std::env::var(env_var).map_err(|e| Error::mnemonic_env(env_var.to_string(), e))?;
+        Self::from_mnemonic(mnemonic.trim(), hd_path, account_prefix, key_type)
+    }
+
+    /// Load the signing account from `mnemonic_env` with the configured `account_prefix` and
+    /// `key_type` if it is configured, otherwise from the key file at `chain_a_key_path`,
+    /// whose public key records its type.
+    pub fn from_config(config: &CosmosChainConfig) -> Result<Self, Error> {
+        match config.mnemonic_env.as_deref() {
+            Some(env_var) => {
+                let account_prefix = config
+                    .account_prefix
+                    .as_deref()
+                    .ok_or_else(|| Error::missing_account_prefix(config.chain_id.clone()))?;
+                Self::from_mnemonic_env(env_var, &config.hd_path, account_prefix, config.key_type)
+            }
+            None => {
+                // The key file records the address in the chain's own format,
+                // it is only re-encoded when a prefix is configured explicitly
+                let account = Self::new(&config.chain_a_key_path, &config.hd_path)?;
+                match config.account_prefix.as_deref() {
+                    Some(account_prefix) => account.with_account_prefix(account_prefix),
+                    None => Ok(account),
+                }
+            }
+        }
+    }
+
+    /// Re-encode the address under another bech32 prefix, the key and address bytes are unchanged.
+    pub fn with_account_prefix(mut self, account_prefix: &str) -> Result<Self, Error> {
+        self.address = encode_bech32_address(account_prefix, &self.address_bytes)?;
+        Ok(self)
+    }
+
     pub fn key_pair(&self) -> Result<Secp256k1KeyPair, Error> {
         self.key_pair.ok_or_else(Error::empty_key_pair)
     }
@@ -75,10 +142,13 @@ impl Secp256k1Account {
 
 #[cfg(test)]
 pub mod account_tests {
-    use crate::chain::CosmosChain;
+    use crate::{chain::CosmosChain, config::cosmos_config_test::test_chain_config, keyring::KeyType};
 
     use super::Secp256k1Account;
 
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    const HD_PATH: &str = "m/44'/118'/0'/0/0";
+
     #[test]
     pub fn account_new_works() {
         let file_path = "/Users/joten/rust_projects/TxAggregator/cosmos_chain/src/config/chain_config.toml";
@@ -91,4 +161,78 @@ pub mod account_tests {
             Err(e) => println!("{}", e),
         }
     }
+
+    #[test]
+    pub fn account_from_mnemonic_works() {
+        let account = Secp256k1Account::from_mnemonic(MNEMONIC, HD_PATH, "cosmos", KeyType::Secp256k1).unwrap();
+
+        assert_eq!(account.address(), "cosmos19rl4cm2hmr8afy4kldpxz3fka4jguq0auqdal4");
+        assert_eq!(account.address_bytes_vec().len(), 20);
+
+        assert!(Secp256k1Account::from_mnemonic(MNEMONIC, "m/44'/118'", "cosmos", KeyType::Secp256k1).is_err());
+        assert!(Secp256k1Account::from_mnemonic("not a mnemonic", HD_PATH, "cosmos", KeyType::Secp256k1).is_err());
+    }
+
+    #[test]
+    pub fn account_prefix_works() {
+        let cosmos_account = Secp256k1Account::from_mnemonic(MNEMONIC, HD_PATH, "cosmos", KeyType::Secp256k1).unwrap();
+        let osmo_account = Secp256k1Account::from_mnemonic(MNEMONIC, HD_PATH, "osmo", KeyType::Secp256k1).unwrap();
+
+        assert_eq!(cosmos_account.address(), "cosmos19rl4cm2hmr8afy4kldpxz3fka4jguq0auqdal4");
+        assert_eq!(osmo_account.address(), "osmo19rl4cm2hmr8afy4kldpxz3fka4jguq0a5m7df8");
+        assert_eq!(cosmos_account.address_bytes_vec(), osmo_account.address_bytes_vec());
+
+        let reencoded = cosmos_account.with_account_prefix("osmo").unwrap();
+        assert_eq!(reencoded.address(), osmo_account.address());
+    }
+
+    #[test]
+    pub fn key_file_account_prefix_works() {
+        let key_path = std::env::temp_dir().join("osmo_account_key.toml");
+        std::fs::write(
+            &key_path,
+            format!(
+                r#"
+name = "osmo_key"
+type = "local"
+address = "osmo19rl4cm2hmr8afy4kldpxz3fka4jguq0a5m7df8"
+pubkey = '{{"@type":"/cosmos.crypto.secp256k1.PubKey","key":"Ak9OKtmcNNYLm6YoPJQxqEGK+GcyEpYfl6d7Y3f80Fti"}}'
+mnemonic = "{}"
+"#,
+                MNEMONIC
+            ),
+        )
+        .unwrap();
+        let mut config = test_chain_config();
+        config.chain_a_key_path = key_path.to_str().unwrap().to_string();
+
+        // Without account_prefix the key file address is kept as is
+        let account = Secp256k1Account::from_config(&config);
+        config.account_prefix = Some("cosmos".to_string());
+        let reencoded = Secp256k1Account::from_config(&config);
+        std::fs::remove_file(&key_path).unwrap();
+
+        assert_eq!(account.unwrap().address(), "osmo19rl4cm2hmr8afy4kldpxz3fka4jguq0a5m7df8");
+        assert_eq!(reencoded.unwrap().address(), "cosmos19rl4cm2hmr8afy4kldpxz3fka4jguq0auqdal4");
+    }
+
+    #[test]
+    pub fn eth_account_from_mnemonic_works() {
+        let account =
+            Secp256k1Account::from_mnemonic(MNEMONIC, "m/44'/60'/0'/0/0", "evmos", KeyType::EthSecp256k1).unwrap();
+
+        // The well-known Ethereum address 0x9858EfFD232B4033E47d90003D41EC34EcaEda94 of this mnemonic
+        assert_eq!(
+            account.address_bytes_vec(),
+            vec![
+                0x98, 0x58, 0xef, 0xfd, 0x23, 0x2b, 0x40, 0x33, 0xe4, 0x7d, 0x90, 0x00, 0x3d, 0x41, 0xec,
+                0x34, 0xec, 0xae, 0xda, 0x94
+            ]
+        );
+        assert!(account.address().starts_with("evmos1"));
+
+        let message = b"ethsecp256k1 sign doc";
+        let signature = account.message_sign(message).unwrap();
+        assert!(account.signature_verify(message, &signature).unwrap());
+    }
 }
@@ -1,53 +1,139 @@
-use http::Uri;
-use ibc_proto::cosmos::auth::v1beta1::{
-    query_client::QueryClient, BaseAccount, EthAccount, QueryAccountRequest,
+use std::time::Duration;
+
+use ibc_proto::{
+    cosmos::{
+        auth::v1beta1::{query_client::QueryClient, BaseAccount, EthAccount, QueryAccountRequest},
+        tx::v1beta1::service_client::ServiceClient,
+    },
+    ibc::core::{
+        channel::v1::query_client::QueryClient as ChannelQueryClient,
+        client::v1::Height as IbcHeight,
+        commitment::v1::MerkleProof,
+    },
+    google::protobuf::Any,
 };
-use log::{error, info, trace};
 use prost::Message;
-use tendermint::abci::response::Info;
-use tendermint_rpc::{Client, HttpClient};
-use tonic::transport::Channel;
-use tracing::{info as tracing_info, info_span};
+use tendermint::{abci::response::Info, block::Height};
+use tendermint_rpc::{endpoint::tx::Response as TxResponse, Client, HttpClient};
+use tonic::{transport::Channel, Code};
+use tracing::{error, info, trace};
 
 use crate::{
-    config::{default::max_grpc_decoding_size, load_cosmos_chain_config, CosmosChainConfig},
-    error::Error,
-    query::{grpc::{self, account::query_detail_account}, types::{Block, BlockResults}},
+    account::Secp256k1Account,
+    config::{load_cosmos_chain_config, max_grpc_decoding_size_from_config, CosmosChainConfig},
+    error::{Error, ErrorDetail},
+    query::{
+        grpc::{
+            self,
+            account::query_detail_account,
+            connect::{is_decode_limit_exceeded, GrpcConnection},
+        },
+        path::{
+            next_sequence_ack_path, next_sequence_recv_path, next_sequence_send_path,
+            ibc_query_path, packet_receipt_path,
+        },
+        types::{Block, BlockResults, QueryHeight},
+    },
     query::trpc,
+    retry::{retry_with_backoff, wait_for_height_with},
+    tx::{
+        create::create_and_sign_tx,
+        estimate::{simulate_tx, SimulateResult},
+        send::{
+            broadcast_tx_and_wait_commit, tx_confirmation_poll_interval_from_config,
+            tx_confirmation_timeout_from_config,
+        },
+        types::memo_from_config,
+    },
 };
 
+/// Interval between two latest height queries while waiting for a height.
+const HEIGHT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
 pub struct CosmosChain {
     pub config: CosmosChainConfig,
-    grpc_client: Option<QueryClient<Channel>>,
+    grpc_connection: GrpcConnection,
     tendermint_rpc: Option<HttpClient>,
 }
 
 impl CosmosChain {
     pub fn new(path: &str) -> Self {
-        let config = load_cosmos_chain_config(path);
-        let config = match config {
-            Ok(c) => c,
+        match Self::try_new(path) {
+            Ok(chain) => chain,
             Err(e) => panic!("{}", e),
-        };
+        }
+    }
 
-        CosmosChain {
+    /// Load the chain from the config at `path`, surfacing an invalid
+    /// config to the caller instead of panicking.
+    pub fn try_new(path: &str) -> Result<Self, Error> {
+        let config = load_cosmos_chain_config(path)?;
+
+        Ok(CosmosChain {
+            grpc_connection: GrpcConnection::new(
+                &config.grpc_addr,
+                max_grpc_decoding_size_from_config(&config).get_bytes() as usize,
+            ),
             config: config,
-            grpc_client: None,
             tendermint_rpc: None,
+        })
+    }
+
+    pub fn tendermint_rpc_client(&mut self) -> Result<&mut HttpClient, Error> {
+        if self.tendermint_rpc.is_none() {
+            let client = HttpClient::new(self.config.tendermint_rpc_addr.as_str())
+                .map_err(|e| Error::trpc("tendermint rpc connect".to_string(), e))?;
+            self.tendermint_rpc = Some(client);
         }
+
+        self.tendermint_rpc
+            .as_mut()
+            .ok_or_else(Error::empty_tendermint_rpc_client)
     }
 
-    pub fn tendermint_rpc_client(&mut self) -> Option<&mut HttpClient> {
-        self.tendermint_rpc.as_mut()
+    pub fn grpc_client(&mut self) -> Result<QueryClient<Channel>, Error> {
+        self.grpc_connection.auth_query_client()
     }
 
-    pub fn grpc_client(&mut self) -> Option<&mut QueryClient<Channel>> {
-        self.grpc_client.as_mut()
+    pub fn grpc_channel_client(&mut self) -> Result<ChannelQueryClient<Channel>, Error> {
+        self.grpc_connection.channel_query_client()
+    }
+
+    pub fn grpc_tx_service_client(&mut self) -> Result<ServiceClient<Channel>, Error> {
+        self.grpc_connection.tx_service_client()
+    }
+
+    // Drop the cached grpc channel when the node could not be reached, so the next query redials it,
+    // and report oversized responses against the configured decoding limit
+    fn handle_grpc_error<T>(&mut self, result: Result<T, Error>) -> Result<T, Error> {
+        let e = match result {
+            Ok(value) => return Ok(value),
+            Err(e) => e,
+        };
+
+        match e.detail() {
+            ErrorDetail::GrpcTransport(_) => {
+                error!("grpc node {} unreachable, resetting connection", self.config.grpc_addr);
+                self.grpc_connection.reset();
+            }
+            ErrorDetail::GrpcStatus(sub) if sub.status.code() == Code::Unavailable => {
+                error!("grpc node {} unreachable, resetting connection", self.config.grpc_addr);
+                self.grpc_connection.reset();
+            }
+            ErrorDetail::GrpcStatus(sub) if is_decode_limit_exceeded(&sub.status) => {
+                return Err(Error::grpc_decode_limit(
+                    sub.query.clone(),
+                    self.grpc_connection.max_decoding_size(),
+                ));
+            }
+            _ => {}
+        }
+
+        Err(e)
     }
 
     pub fn tendermint_rpc_connect(&mut self) {
         trace!("tendermint rpc connect");
-        tracing_info!("tendermint rpc connect access");
 
         let client = match HttpClient::new(self.config.tendermint_rpc_addr.as_str()) {
             Ok(client) => client,
@@ -59,72 +145,308 @@ impl CosmosChain {
         info!("tendermint rpc connect success");
     }
 
+    #[tracing::instrument(skip(self), fields(chain_id = %self.config.chain_id))]
     pub async fn query_abci_info(&mut self) -> Result<Info, Error> {
-        let trpc = self
-            .tendermint_rpc_client()
-            .ok_or_else(Error::empty_tendermint_rpc_client)?;
+        let trpc = self.tendermint_rpc_client()?;
         trpc::abci::abci_info(trpc).await
     }
 
+    #[tracing::instrument(skip(self), fields(chain_id = %self.config.chain_id))]
     pub async fn grpc_connect(&mut self) {
         trace!("grpc connect");
-        tracing_info!("grpc_connect access");
-        let grpc_addr = self
-            .config
-            .grpc_addr
-            .as_str()
-            .parse::<Uri>()
-            .expect("grpc address parse error!");
-        let mut client = match QueryClient::connect(grpc_addr).await {
-            Ok(client) => client,
-            Err(e) => panic!("grpc connect error: {:?}", e),
-        };
-
-        client = client.max_decoding_message_size(max_grpc_decoding_size().get_bytes() as usize);
-        self.grpc_client = Some(client);
 
-        info!("grpc connect success");
+        // The channel is dialed lazily by the first query
+        if let Err(e) = self.grpc_connection.channel() {
+            panic!("grpc connect error: {:?}", e);
+        }
     }
 
+    #[tracing::instrument(skip(self), fields(chain_id = %self.config.chain_id))]
     pub async fn query_detail_account_by_address(
         &mut self,
         account_addr: &str,
     ) -> Result<BaseAccount, Error> {
-        let grpc_client = self.grpc_client().ok_or_else(Error::empty_grpc_client)?;
+        let mut grpc_client = self.grpc_client()?;
         trace!("query detail account by address");
 
-        grpc::account::query_detail_account(grpc_client, account_addr).await
+        let result = grpc::account::query_detail_account(&mut grpc_client, account_addr).await;
+        self.handle_grpc_error(result)
     }
 
+    #[tracing::instrument(skip(self), fields(chain_id = %self.config.chain_id))]
     pub async fn query_all_accounts(&mut self) -> Result<Vec<BaseAccount>, Error> {
-        // let span = info_span!("query_all_accounts");
-        // let _span = span.enter();
-
-        let grpc_client = self.grpc_client().ok_or_else(Error::empty_grpc_client)?;
+        let mut grpc_client = self.grpc_client()?;
         trace!("query all accounts");
-        tracing_info!("query all accounts access");
 
-        grpc::account::query_all_account(grpc_client).await
+        let result = grpc::account::query_all_account(&mut grpc_client).await;
+        self.handle_grpc_error(result)
     }
 
+    #[tracing::instrument(skip(self), fields(chain_id = %self.config.chain_id))]
     pub async fn query_latest_block(&mut self) -> Result<Block, Error> {
-        let trpc = self.tendermint_rpc_client().ok_or_else(Error::empty_tendermint_rpc_client)?;
+        let trpc = self.tendermint_rpc_client()?;
         trace!("query latest block");
 
         trpc::block::latest_block(trpc).await
     }
 
+    #[tracing::instrument(skip(self), fields(chain_id = %self.config.chain_id))]
     pub async fn query_latest_block_results(&mut self) -> Result<BlockResults, Error> {
-        let trpc = self.tendermint_rpc_client().ok_or_else(Error::empty_tendermint_rpc_client)?;
+        let trpc = self.tendermint_rpc_client()?;
         trace!("query latest block results");
 
         trpc::block::latest_block_results(trpc).await
     }
+
+    #[tracing::instrument(skip(self), fields(chain_id = %self.config.chain_id))]
+    pub async fn query_latest_height(&mut self) -> Result<Height, Error> {
+        let trpc = self.tendermint_rpc_client()?.clone();
+        trace!("query latest height");
+
+        let abci_info = retry_with_backoff(&self.config.query_retry, "query latest height", || {
+            let mut trpc = trpc.clone();
+            async move { trpc::abci::abci_info(&mut trpc).await }
+        })
+        .await?;
+
+        Ok(abci_info.last_block_height)
+    }
+
+    /// Wait until the chain reaches `target_height`, failing once `timeout` expires.
+    #[tracing::instrument(skip(self), fields(chain_id = %self.config.chain_id))]
+    pub async fn wait_for_height(&mut self, target_height: Height, timeout: Duration) -> Result<Height, Error> {
+        let trpc = self.tendermint_rpc_client()?.clone();
+
+        wait_for_height_with(target_height, timeout, HEIGHT_POLL_INTERVAL, || {
+            let mut trpc = trpc.clone();
+            async move {
+                trpc::abci::abci_info(&mut trpc)
+                    .await
+                    .map(|abci_info| abci_info.last_block_height)
+            }
+        })
+        .await
+    }
+
+    /// Simulate `msgs` signed by `account` without broadcasting them,
+    /// returning the gas estimate and the emitted events.
+    #[tracing::instrument(skip(self, account, msgs), fields(chain_id = %self.config.chain_id))]
+    pub async fn simulate_tx(
+        &mut self,
+        account: &Secp256k1Account,
+        msgs: &[Any],
+    ) -> Result<SimulateResult, Error> {
+        let account_detail = self
+            .query_detail_account_by_address(account.address().as_str())
+            .await?;
+        let memo = memo_from_config(&self.config, msgs.len()).map_err(Error::tx_memo)?;
+        let (tx, _) = create_and_sign_tx(&self.config, account, &account_detail, &memo, msgs)?;
+
+        let mut grpc_client = self.grpc_tx_service_client()?;
+        trace!("simulate tx with {} messages", msgs.len());
+
+        let result = simulate_tx(&mut grpc_client, tx).await;
+        self.handle_grpc_error(result)?.try_into()
+    }
+
+    /// Broadcast a signed tx and wait until it is committed, failing once
+    /// the configured `tx_confirmation_timeout` expires.
+    #[tracing::instrument(skip(self, tx_bytes), fields(chain_id = %self.config.chain_id))]
+    pub async fn send_tx_and_wait_commit(&mut self, tx_bytes: Vec<u8>) -> Result<TxResponse, Error> {
+        let timeout = tx_confirmation_timeout_from_config(&self.config);
+        let poll_interval = tx_confirmation_poll_interval_from_config(&self.config);
+        let trpc = self.tendermint_rpc_client()?;
+
+        let response = broadcast_tx_and_wait_commit(trpc, tx_bytes, timeout, poll_interval).await?;
+        info!("tx {} committed at height {}", response.hash, response.height);
+
+        Ok(response)
+    }
+
+    /// Query a key of the IBC store, returning the stored value and,
+    /// if `prove` is set, its merkle proof.
+    #[tracing::instrument(skip(self), fields(chain_id = %self.config.chain_id))]
+    pub async fn query_ibc_store(
+        &mut self,
+        key_path: String,
+        height_query: QueryHeight,
+        prove: bool,
+    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
+        let query_path = ibc_query_path(&self.config.store_prefix);
+        let trpc = self.tendermint_rpc_client()?;
+        trace!("query ibc store: {}", key_path);
+
+        let response = trpc::abci::abci_query(
+            trpc,
+            query_path,
+            key_path.into_bytes(),
+            height_query,
+            prove,
+        )
+        .await?;
+
+        let proof = match response.proof {
+            Some(proof_ops) if prove => Some(trpc::abci::merkle_proof_from_proof_ops(&proof_ops)?),
+            _ => None,
+        };
+
+        Ok((response.value, proof))
+    }
+
+    /// Query the packet receipt of an unordered channel. An empty value together
+    /// with its proof proves that the packet has not been received.
+    #[tracing::instrument(skip(self), fields(chain_id = %self.config.chain_id))]
+    pub async fn query_packet_receipt(
+        &mut self,
+        port_id: &str,
+        channel_id: &str,
+        sequence: u64,
+        height_query: QueryHeight,
+        prove: bool,
+    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
+        let key_path = packet_receipt_path(port_id, channel_id, sequence);
+        self.query_ibc_store(key_path, height_query, prove).await
+    }
+
+    /// Query the next sequence the channel end will send, with its proof if `prove` is set.
+    #[tracing::instrument(skip(self), fields(chain_id = %self.config.chain_id))]
+    pub async fn query_next_sequence_send(
+        &mut self,
+        port_id: &str,
+        channel_id: &str,
+        height_query: QueryHeight,
+        prove: bool,
+    ) -> Result<(u64, Option<MerkleProof>), Error> {
+        let key_path = next_sequence_send_path(port_id, channel_id);
+        self.query_next_sequence(key_path, height_query, prove).await
+    }
+
+    /// Query the next sequence the channel end expects to receive, with its proof if `prove` is set.
+    /// Timing out a packet on an ordered channel requires this proof.
+    #[tracing::instrument(skip(self), fields(chain_id = %self.config.chain_id))]
+    pub async fn query_next_sequence_recv(
+        &mut self,
+        port_id: &str,
+        channel_id: &str,
+        height_query: QueryHeight,
+        prove: bool,
+    ) -> Result<(u64, Option<MerkleProof>), Error> {
+        let key_path = next_sequence_recv_path(port_id, channel_id);
+        self.query_next_sequence(key_path, height_query, prove).await
+    }
+
+    /// Query the next sequence the channel end expects to acknowledge, with its proof if `prove` is set.
+    #[tracing::instrument(skip(self), fields(chain_id = %self.config.chain_id))]
+    pub async fn query_next_sequence_ack(
+        &mut self,
+        port_id: &str,
+        channel_id: &str,
+        height_query: QueryHeight,
+        prove: bool,
+    ) -> Result<(u64, Option<MerkleProof>), Error> {
+        let key_path = next_sequence_ack_path(port_id, channel_id);
+        self.query_next_sequence(key_path, height_query, prove).await
+    }
+
+    // The next sequences are read as raw keys of the IBC store over ABCI rather than gRPC:
+    // the store query returns the value together with its merkle proof at the queried height
+    // in one call, and ibc-proto has no gRPC query for the next send or ack sequence at all.
+    async fn query_next_sequence(
+        &mut self,
+        key_path: String,
+        height_query: QueryHeight,
+        prove: bool,
+    ) -> Result<(u64, Option<MerkleProof>), Error> {
+        let (value, proof) = self.query_ibc_store(key_path.clone(), height_query, prove).await?;
+        let sequence = trpc::abci::decode_sequence(&key_path, &value)?;
+
+        Ok((sequence, proof))
+    }
+
+    /// Filter the given acknowledgement sequences down to the ones
+    /// this chain has not processed yet.
+    #[tracing::instrument(skip(self), fields(chain_id = %self.config.chain_id))]
+    pub async fn query_unreceived_acks(
+        &mut self,
+        port_id: &str,
+        channel_id: &str,
+        sequences: &[u64],
+    ) -> Result<Vec<u64>, Error> {
+        let mut grpc_client = self.grpc_channel_client()?;
+        trace!("query unreceived acks");
+
+        let result = grpc::channel::query_unreceived_acks(&mut grpc_client, port_id, channel_id, sequences).await;
+        self.handle_grpc_error(result)
+    }
+
+    /// Filter the given packet sequences down to the ones this chain has not received yet.
+    #[tracing::instrument(skip(self), fields(chain_id = %self.config.chain_id))]
+    pub async fn query_unreceived_packets(
+        &mut self,
+        port_id: &str,
+        channel_id: &str,
+        sequences: &[u64],
+    ) -> Result<Vec<u64>, Error> {
+        let mut grpc_client = self.grpc_channel_client()?;
+        trace!("query unreceived packets");
+
+        let result = grpc::channel::query_unreceived_packets(&mut grpc_client, port_id, channel_id, sequences).await;
+        self.handle_grpc_error(result)
+    }
+
+    /// Enumerate the sequences of all packet commitments outstanding on the channel,
+    /// along with the height they were queried at. Feeding them to the counterparty's
+    /// `query_unreceived_packets` gives the relay backlog.
+    #[tracing::instrument(skip(self), fields(chain_id = %self.config.chain_id))]
+    pub async fn query_packet_commitments(
+        &mut self,
+        port_id: &str,
+        channel_id: &str,
+    ) -> Result<(Vec<u64>, Option<IbcHeight>), Error> {
+        let mut grpc_client = self.grpc_channel_client()?;
+        trace!("query packet commitments");
+
+        let result = grpc::channel::query_packet_commitments(&mut grpc_client, port_id, channel_id).await;
+        self.handle_grpc_error(result)
+    }
 }
 
 #[cfg(test)]
 pub mod chain_tests {
+    use std::{
+        io::Write,
+        net::SocketAddr,
+        sync::{atomic::Ordering, Arc, Mutex},
+    };
+
+    use byte_unit::Byte;
+    use ics23::{commitment_proof::Proof, CommitmentProof, ExistenceProof, NonExistenceProof};
     use log::info;
+    use prost::Message;
+    use tendermint::{
+        block::Height,
+        merkle::proof::{ProofOp, ProofOps},
+    };
+    use tendermint_rpc::endpoint::abci_query::AbciQuery;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+    use tonic::Status;
+    use tracing::Level;
+    use tracing_subscriber::fmt::MakeWriter;
+
+    use crate::{
+        config::{
+            cosmos_config_test::{test_chain_config, write_test_chain_config},
+            CosmosChainConfig,
+        },
+        error::{Error, ErrorDetail},
+        query::{
+            grpc::channel::channel_tests::{serve_mock_channel_query, MockChannelQuery},
+            types::QueryHeight,
+        },
+    };
 
     use super::CosmosChain;
 
@@ -132,6 +454,98 @@ pub mod chain_tests {
         let _ = env_logger::builder().is_test(true).try_init();
     }
 
+    // Serve `response` to every JSON-RPC request on a local port, recording the request bodies
+    pub async fn mock_tendermint_rpc(response: AbciQuery) -> (SocketAddr, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(vec![]));
+
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": "",
+            "result": { "response": response },
+        })
+        .to_string();
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = vec![];
+                let mut buf = [0_u8; 4096];
+                // Read the headers, then as much of the body as they announce
+                loop {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request).to_string();
+                    if let Some(end) = text.find("\r\n\r\n") {
+                        let length = text[..end]
+                            .lines()
+                            .find_map(|line| {
+                                let line = line.to_lowercase();
+                                let length = line.strip_prefix("content-length:")?;
+                                length.trim().parse::<usize>().ok()
+                            })
+                            .unwrap_or_default();
+                        if request.len() >= end + 4 + length {
+                            recorded.lock().unwrap().push(text[end + 4..].to_string());
+                            break;
+                        }
+                    }
+                }
+
+                let reply = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                stream.write_all(reply.as_bytes()).await.unwrap();
+            }
+        });
+
+        (addr, requests)
+    }
+
+    pub fn cosmos_chain_from_config(name: &str, config: &CosmosChainConfig) -> CosmosChain {
+        let file_path = write_test_chain_config(name, config);
+        let cosmos_chain = CosmosChain::new(file_path.to_str().unwrap());
+        std::fs::remove_file(&file_path).unwrap();
+
+        cosmos_chain
+    }
+
+    // A chain whose tendermint rpc endpoint is `rpc_addr`
+    pub fn mock_cosmos_chain(name: &str, rpc_addr: SocketAddr) -> CosmosChain {
+        let mut config = test_chain_config();
+        config.tendermint_rpc_addr = format!("http://{}", rpc_addr);
+
+        cosmos_chain_from_config(name, &config)
+    }
+
+    // An ABCI query response for `key` carrying `proof` as its single proof op
+    pub fn mock_abci_query(key: &[u8], value: &[u8], proof: &CommitmentProof) -> AbciQuery {
+        AbciQuery {
+            key: key.to_vec(),
+            value: value.to_vec(),
+            proof: Some(ProofOps {
+                ops: vec![ProofOp {
+                    field_type: "ics23:iavl".to_string(),
+                    key: key.to_vec(),
+                    data: proof.encode_to_vec(),
+                }],
+            }),
+            height: Height::from(100_u32),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    pub fn try_new_with_missing_config_fails() {
+        init();
+        let file_path = "/nonexistent/TxAggregator/chain_config.toml";
+
+        assert!(CosmosChain::try_new(file_path).is_err());
+    }
+
     #[actix_rt::test]
     pub async fn grpc_connect_works() {
         init();
@@ -141,4 +555,211 @@ pub mod chain_tests {
 
         cosmos_chain.grpc_connect().await;
     }
+
+    #[actix_rt::test]
+    pub async fn query_packet_receipt_works() {
+        init();
+        let key = b"receipts/ports/transfer/channels/channel-0/sequences/1".to_vec();
+        // The packet has not been received, so the receipt comes with a non-existence proof
+        let proof = CommitmentProof {
+            proof: Some(Proof::Nonexist(NonExistenceProof {
+                key: key.clone(),
+                left: None,
+                right: None,
+            })),
+        };
+        let (rpc_addr, requests) = mock_tendermint_rpc(mock_abci_query(&key, &[], &proof)).await;
+        let mut cosmos_chain = mock_cosmos_chain("packet_receipt", rpc_addr);
+
+        cosmos_chain.tendermint_rpc_connect();
+        let (receipt, merkle_proof) = cosmos_chain
+            .query_packet_receipt("transfer", "channel-0", 1, QueryHeight::Latest, true)
+            .await
+            .expect("query packet receipt error!");
+
+        info!("receipt: {:?}", receipt);
+        assert!(receipt.is_empty());
+        assert_eq!(merkle_proof.unwrap().proofs, vec![proof]);
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        let request: serde_json::Value = serde_json::from_str(&requests[0]).unwrap();
+        assert_eq!(request["method"], "abci_query");
+        assert_eq!(request["params"]["path"], "store/ibc/key");
+        assert_eq!(request["params"]["prove"], true);
+        assert_eq!(
+            request["params"]["data"].as_str().unwrap().to_lowercase().as_bytes(),
+            subtle_encoding::hex::encode(&key).as_slice()
+        );
+    }
+
+    #[actix_rt::test]
+    pub async fn query_next_sequence_recv_works() {
+        init();
+        let key = b"nextSequenceRecv/ports/transfer/channels/channel-0".to_vec();
+        let value = 7_u64.to_be_bytes().to_vec();
+        let proof = CommitmentProof {
+            proof: Some(Proof::Exist(ExistenceProof {
+                key: key.clone(),
+                value: value.clone(),
+                leaf: None,
+                path: vec![],
+            })),
+        };
+        let (rpc_addr, requests) = mock_tendermint_rpc(mock_abci_query(&key, &value, &proof)).await;
+        let mut cosmos_chain = mock_cosmos_chain("next_sequence_recv", rpc_addr);
+
+        cosmos_chain.tendermint_rpc_connect();
+        let (sequence, merkle_proof) = cosmos_chain
+            .query_next_sequence_recv("transfer", "channel-0", QueryHeight::Latest, true)
+            .await
+            .unwrap();
+
+        assert_eq!(sequence, 7);
+        assert_eq!(merkle_proof.unwrap().proofs, vec![proof]);
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        let request: serde_json::Value = serde_json::from_str(&requests[0]).unwrap();
+        assert_eq!(request["params"]["path"], "store/ibc/key");
+        assert_eq!(request["params"]["prove"], true);
+    }
+
+    #[actix_rt::test]
+    pub async fn grpc_channel_reuse_works() {
+        let (grpc_addr, connections) = serve_mock_channel_query(MockChannelQuery::new([2, 4])).await;
+        let mut config = test_chain_config();
+        config.grpc_addr = format!("http://{}", grpc_addr);
+        let mut cosmos_chain = cosmos_chain_from_config("grpc_channel_reuse", &config);
+
+        let unreceived = cosmos_chain
+            .query_unreceived_acks("transfer", "channel-0", &[1, 2])
+            .await
+            .unwrap();
+        assert_eq!(unreceived, vec![2]);
+        let unreceived = cosmos_chain
+            .query_unreceived_acks("transfer", "channel-0", &[3, 4])
+            .await
+            .unwrap();
+        assert_eq!(unreceived, vec![4]);
+
+        assert_eq!(cosmos_chain.grpc_connection.dials(), 1);
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    pub fn handle_grpc_decode_limit_works() {
+        let mut config = test_chain_config();
+        config.max_grpc_decoding_size = Some(Byte::from_bytes(1024));
+        let mut cosmos_chain = cosmos_chain_from_config("handle_grpc_decode_limit", &config);
+
+        let status = Status::out_of_range(
+            "Error, message length too large: found 40000000 bytes, the limit is: 1024 bytes",
+        );
+        let result: Result<(), Error> =
+            cosmos_chain.handle_grpc_error(Err(Error::grpc_status(status, "mock_query".to_owned())));
+
+        match result {
+            Err(e) => match e.detail() {
+                ErrorDetail::GrpcDecodeLimit(sub) => {
+                    assert_eq!(sub.query, "mock_query");
+                    assert_eq!(sub.limit, 1024);
+                }
+                _ => panic!("expected a decode limit error, got {}", e),
+            },
+            Ok(_) => panic!("the error should be kept"),
+        }
+    }
+
+    #[actix_rt::test]
+    pub async fn grpc_decode_limit_from_config_works() {
+        // A response listing a hundred sequences does not fit in 64 bytes
+        let (grpc_addr, _) = serve_mock_channel_query(MockChannelQuery::new(1..=100)).await;
+        let mut config = test_chain_config();
+        config.grpc_addr = format!("http://{}", grpc_addr);
+        config.max_grpc_decoding_size = Some(Byte::from_bytes(64));
+        let mut cosmos_chain = cosmos_chain_from_config("grpc_decode_limit_from_config", &config);
+
+        let sequences: Vec<u64> = (1..=100).collect();
+        let result = cosmos_chain
+            .query_unreceived_acks("transfer", "channel-0", &sequences)
+            .await;
+
+        match result {
+            Err(e) => match e.detail() {
+                ErrorDetail::GrpcDecodeLimit(sub) => assert_eq!(sub.limit, 64),
+                _ => panic!("expected a decode limit error, got {}", e),
+            },
+            Ok(_) => panic!("the response should exceed the configured limit"),
+        }
+
+        // Within the default limit the same query succeeds
+        config.max_grpc_decoding_size = None;
+        let mut cosmos_chain = cosmos_chain_from_config("grpc_decode_limit_default", &config);
+        let unreceived = cosmos_chain
+            .query_unreceived_acks("transfer", "channel-0", &sequences)
+            .await
+            .unwrap();
+        assert_eq!(unreceived, sequences);
+    }
+
+    // Collects what the fmt subscriber writes, so tests can assert on the emitted spans
+    #[derive(Clone, Default)]
+    struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for LogBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for LogBuffer {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[actix_rt::test]
+    pub async fn chain_span_fields_works() {
+        let logs = LogBuffer::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(Level::TRACE)
+            .with_ansi(false)
+            .with_writer(logs.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let key = b"receipts/ports/transfer/channels/channel-0/sequences/1".to_vec();
+        let proof = CommitmentProof {
+            proof: Some(Proof::Nonexist(NonExistenceProof {
+                key: key.clone(),
+                left: None,
+                right: None,
+            })),
+        };
+        let (rpc_addr, _) = mock_tendermint_rpc(mock_abci_query(&key, &[], &proof)).await;
+        let mut cosmos_chain = mock_cosmos_chain("chain_span_fields", rpc_addr);
+
+        cosmos_chain
+            .query_packet_receipt("transfer", "channel-0", 1, QueryHeight::Latest, false)
+            .await
+            .unwrap();
+
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        let line = logs
+            .lines()
+            .find(|line| line.contains("query ibc store"))
+            .expect("the store query should be logged");
+        let span = &line[line.find("query_packet_receipt{").expect("the event should be in the chain span")..];
+        assert!(span.contains(r#"port_id="transfer""#));
+        assert!(span.contains(r#"channel_id="channel-0""#));
+        assert!(span.contains("chain_id=test_chain"));
+    }
 }
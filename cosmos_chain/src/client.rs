@@ -0,0 +1,241 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{config::CosmosChainConfig, error::Error};
+
+/// Default share of the unbonding period a client may trust a header for.
+pub const DEFAULT_TRUSTING_PERIOD_FRACTION: f64 = 2.0 / 3.0;
+
+/// Default tolerated clock drift between the chains, in seconds.
+pub const DEFAULT_MAX_CLOCK_DRIFT: u64 = 15;
+
+/// Default expected interval between two blocks of the counterparty, in seconds.
+pub const DEFAULT_EXPECTED_BLOCK_INTERVAL: u64 = 5;
+
+/// Default number of block intervals the clock drift must at least cover.
+pub const DEFAULT_CLOCK_DRIFT_BLOCK_INTERVALS: u32 = 2;
+
+/// Fraction of the validator set voting power that must sign a header
+/// for the client to trust it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustLevel {
+    pub numerator: u64,
+    pub denominator: u64,
+}
+
+impl TrustLevel {
+    pub const ONE_THIRD: Self = Self {
+        numerator: 1,
+        denominator: 3,
+    };
+
+    pub const TWO_THIRDS: Self = Self {
+        numerator: 2,
+        denominator: 3,
+    };
+
+    /// A trust level is only meaningful within [1/3, 1]: below 1/3 a client
+    /// can be fooled by a minority of the validators, above 1 it can never verify.
+    pub fn new(numerator: u64, denominator: u64) -> Result<Self, Error> {
+        let too_small = (numerator as u128) * 3 < denominator as u128;
+        if denominator == 0 || numerator > denominator || too_small {
+            return Err(Error::invalid_trust_level(numerator, denominator));
+        }
+
+        Ok(Self {
+            numerator,
+            denominator,
+        })
+    }
+}
+
+impl Default for TrustLevel {
+    fn default() -> Self {
+        Self::ONE_THIRD
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientSettings {
+    pub max_clock_drift: Duration,
+    pub trusting_period: Duration,
+    pub trust_level: TrustLevel,
+}
+
+impl ClientSettings {
+    /// The trusting period must stay within `trusting_period_fraction` of the unbonding
+    /// period, otherwise a header could still be trusted after its validators unbonded.
+    pub fn new(
+        max_clock_drift: Duration,
+        trusting_period: Duration,
+        unbonding_period: Duration,
+        trusting_period_fraction: f64,
+        trust_level: TrustLevel,
+    ) -> Result<Self, Error> {
+        let trust_level = TrustLevel::new(trust_level.numerator, trust_level.denominator)?;
+
+        let valid_fraction = trusting_period_fraction > 0.0 && trusting_period_fraction < 1.0;
+        if !valid_fraction
+            || unbonding_period.is_zero()
+            || trusting_period.as_secs_f64() / unbonding_period.as_secs_f64() > trusting_period_fraction
+        {
+            return Err(Error::invalid_trusting_period(
+                trusting_period,
+                unbonding_period,
+                trusting_period_fraction,
+            ));
+        }
+
+        Ok(Self {
+            max_clock_drift,
+            trusting_period,
+            trust_level,
+        })
+    }
+
+    /// Build the settings from the chain config, validating the trust level, the trusting
+    /// period against `unbonding_period` and the clock drift against the block interval.
+    pub fn from_config(config: &CosmosChainConfig, unbonding_period: Duration) -> Result<Self, Error> {
+        let trusting_period_fraction = trusting_period_fraction_from_config(config);
+        let trusting_period = config
+            .trusting_period
+            .map(Duration::from_secs)
+            .unwrap_or_else(|| default_trusting_period(unbonding_period, trusting_period_fraction));
+        let max_clock_drift =
+            Duration::from_secs(config.max_clock_drift.unwrap_or(DEFAULT_MAX_CLOCK_DRIFT));
+        let expected_block_interval = Duration::from_secs(
+            config
+                .expected_block_interval
+                .unwrap_or(DEFAULT_EXPECTED_BLOCK_INTERVAL),
+        );
+
+        validate_max_clock_drift(
+            max_clock_drift,
+            expected_block_interval,
+            config
+                .clock_drift_block_intervals
+                .unwrap_or(DEFAULT_CLOCK_DRIFT_BLOCK_INTERVALS),
+        )?;
+
+        Self::new(
+            max_clock_drift,
+            trusting_period,
+            unbonding_period,
+            trusting_period_fraction,
+            config.trust_level.unwrap_or_default(),
+        )
+    }
+}
+
+/// A clock drift shorter than a few block intervals makes the client reject
+/// valid headers as being in the future.
+pub fn validate_max_clock_drift(
+    max_clock_drift: Duration,
+    expected_block_interval: Duration,
+    block_intervals: u32,
+) -> Result<(), Error> {
+    let min_clock_drift = expected_block_interval.saturating_mul(block_intervals);
+    if max_clock_drift < min_clock_drift {
+        return Err(Error::max_clock_drift_too_small(max_clock_drift, min_clock_drift));
+    }
+
+    Ok(())
+}
+
+pub fn default_trusting_period(unbonding_period: Duration, trusting_period_fraction: f64) -> Duration {
+    unbonding_period.mul_f64(trusting_period_fraction)
+}
+
+pub fn trusting_period_fraction_from_config(config: &CosmosChainConfig) -> f64 {
+    config
+        .trusting_period_fraction
+        .unwrap_or(DEFAULT_TRUSTING_PERIOD_FRACTION)
+}
+
+#[cfg(test)]
+pub mod client_tests {
+    use std::time::Duration;
+
+    use super::{
+        validate_max_clock_drift, ClientSettings, TrustLevel, DEFAULT_CLOCK_DRIFT_BLOCK_INTERVALS,
+        DEFAULT_TRUSTING_PERIOD_FRACTION,
+    };
+
+    const DAY: u64 = 86400;
+
+    fn settings(numerator: u64, denominator: u64) -> bool {
+        ClientSettings::new(
+            Duration::from_secs(5),
+            Duration::from_secs(14 * DAY),
+            Duration::from_secs(21 * DAY),
+            DEFAULT_TRUSTING_PERIOD_FRACTION,
+            TrustLevel {
+                numerator,
+                denominator,
+            },
+        )
+        .is_ok()
+    }
+
+    #[test]
+    pub fn trust_level_bounds_works() {
+        assert!(settings(1, 3));
+        assert!(settings(2, 3));
+        assert!(settings(1, 1));
+
+        assert!(!settings(0, 1));
+        assert!(!settings(4, 3));
+        assert!(!settings(1, 4));
+        assert!(!settings(1, 0));
+    }
+
+    fn periods(trusting_days: u64, unbonding_days: u64, fraction: f64) -> bool {
+        ClientSettings::new(
+            Duration::from_secs(5),
+            Duration::from_secs(trusting_days * DAY),
+            Duration::from_secs(unbonding_days * DAY),
+            fraction,
+            TrustLevel::default(),
+        )
+        .is_ok()
+    }
+
+    #[test]
+    pub fn trusting_period_bounds_works() {
+        assert!(periods(14, 21, DEFAULT_TRUSTING_PERIOD_FRACTION));
+        assert!(periods(10, 21, 0.5));
+
+        assert!(!periods(15, 21, DEFAULT_TRUSTING_PERIOD_FRACTION));
+        assert!(!periods(21, 21, DEFAULT_TRUSTING_PERIOD_FRACTION));
+        assert!(!periods(30, 21, DEFAULT_TRUSTING_PERIOD_FRACTION));
+        assert!(!periods(7, 21, 1.0));
+        assert!(!periods(7, 21, 0.0));
+    }
+
+    #[test]
+    pub fn max_clock_drift_bounds_works() {
+        let block_interval = Duration::from_secs(5);
+
+        assert!(validate_max_clock_drift(
+            Duration::from_secs(10),
+            block_interval,
+            DEFAULT_CLOCK_DRIFT_BLOCK_INTERVALS
+        )
+        .is_ok());
+        assert!(validate_max_clock_drift(
+            Duration::from_secs(40),
+            block_interval,
+            DEFAULT_CLOCK_DRIFT_BLOCK_INTERVALS
+        )
+        .is_ok());
+
+        assert!(validate_max_clock_drift(
+            Duration::from_secs(3),
+            block_interval,
+            DEFAULT_CLOCK_DRIFT_BLOCK_INTERVALS
+        )
+        .is_err());
+        assert!(validate_max_clock_drift(Duration::from_secs(12), block_interval, 3).is_err());
+    }
+}
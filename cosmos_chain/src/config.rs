@@ -1,7 +1,8 @@
+use byte_unit::Byte;
 use serde::{Serialize, Deserialize};
 use utils::file::toml_file;
 
-use crate::{error::Error, tx::types::GasPrice};
+use crate::{client::TrustLevel, error::Error, keyring::KeyType, retry::RetryPolicy, tx::types::GasPrice};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CosmosChainConfig {
@@ -12,21 +13,80 @@ pub struct CosmosChainConfig {
     pub chain_a_key_path: String,
     pub chain_b_key_path: String,
     pub hd_path: String,
+    pub account_prefix: Option<String>,
+    pub mnemonic_env: Option<String>,
+    #[serde(default)]
+    pub key_type: KeyType,
 
 
     pub default_gas: Option<u64>,
     pub max_gas: Option<u64>,
+    #[serde(alias = "gas_adjustment")]
     pub gas_multiplier: Option<f64>,
     pub fee_granter: Option<String>,
+    pub fee_payer: Option<String>,
     pub gas_price: GasPrice,
 
+    pub max_grpc_decoding_size: Option<Byte>,
+
+    pub trust_level: Option<TrustLevel>,
+    pub trusting_period: Option<u64>,
+    pub trusting_period_fraction: Option<f64>,
+    pub max_clock_drift: Option<u64>,
+    pub expected_block_interval: Option<u64>,
+    pub clock_drift_block_intervals: Option<u32>,
+
+    pub memo_prefix: Option<String>,
+    pub memo_template: Option<String>,
+
+    pub tx_confirmation_timeout: Option<u64>,
+    pub tx_confirmation_poll_interval: Option<u64>,
+
+    #[serde(default)]
+    pub query_retry: RetryPolicy,
+
+    #[serde(default = "default::store_prefix")]
+    pub store_prefix: String,
 }
 
 pub fn load_cosmos_chain_config(path: &str) -> Result<CosmosChainConfig, Error> {
     let config: CosmosChainConfig = toml_file::toml_file_read(path).map_err(|e| Error::load_cosmos_chain_conifg(e))?;
+    validate_store_prefix(&config.store_prefix)?;
+    validate_account_prefix(&config)?;
     Ok(config)
 }
 
+/// An address derived from a mnemonic has no bech32 prefix of its own, and an `eth_secp256k1`
+/// chain never uses `cosmos`, so either one requires the chain's `account_prefix`.
+pub fn validate_account_prefix(config: &CosmosChainConfig) -> Result<(), Error> {
+    let requires_prefix = config.mnemonic_env.is_some() || config.key_type == KeyType::EthSecp256k1;
+    if requires_prefix && config.account_prefix.is_none() {
+        return Err(Error::missing_account_prefix(config.chain_id.clone()));
+    }
+
+    Ok(())
+}
+
+/// The store prefix is part of every commitment path, a wrong one only shows up
+/// as a failed proof verification on the counterparty.
+pub fn validate_store_prefix(store_prefix: &str) -> Result<(), Error> {
+    let invalid = store_prefix.is_empty()
+        || store_prefix
+            .chars()
+            .any(|c| c == '/' || c.is_whitespace());
+    if invalid {
+        return Err(Error::invalid_store_prefix(store_prefix.to_string()));
+    }
+
+    Ok(())
+}
+
+pub fn max_grpc_decoding_size_from_config(config: &CosmosChainConfig) -> Byte {
+    config
+        .max_grpc_decoding_size
+        .unwrap_or_else(default::max_grpc_decoding_size)
+}
+
 pub mod default {
     use byte_unit::Byte;
 
@@ -34,13 +94,51 @@ pub mod default {
     pub fn max_grpc_decoding_size() -> Byte {
         Byte::from_bytes(33554432)
     }
+
+    pub fn store_prefix() -> String {
+        "ibc".to_string()
+    }
 }
 
 #[cfg(test)]
 pub mod cosmos_config_test {
+    use std::path::PathBuf;
+
     use utils::file::toml_file;
 
-    use super::CosmosChainConfig;
+    use crate::{error::ErrorDetail, keyring::KeyType};
+
+    use super::{
+        load_cosmos_chain_config, validate_account_prefix, validate_store_prefix, CosmosChainConfig,
+    };
+
+    // A minimal valid config, tests override the fields they exercise
+    pub fn test_chain_config() -> CosmosChainConfig {
+        toml::from_str(
+            r#"
+chain_id = "test_chain"
+grpc_addr = "http://127.0.0.1:9090"
+tendermint_rpc_addr = "http://127.0.0.1:26657"
+blockchain_api_addr = "http://127.0.0.1:1317"
+chain_a_key_path = "key_a.toml"
+chain_b_key_path = "key_b.toml"
+hd_path = "m/44'/118'/0'/0/0"
+
+[gas_price]
+price = 0.1
+denom = "stake"
+"#,
+        )
+        .unwrap()
+    }
+
+    // Write `config` to a temporary file, for the code paths that load it from disk
+    pub fn write_test_chain_config(name: &str, config: &CosmosChainConfig) -> PathBuf {
+        let file_path = std::env::temp_dir().join(format!("{}_chain_config.toml", name));
+        std::fs::write(&file_path, toml::to_string(config).unwrap()).unwrap();
+
+        file_path
+    }
 
     #[test]
     pub fn read_cosmos_chain_config_works() {
@@ -50,4 +148,61 @@ pub mod cosmos_config_test {
 
         println!("{:#?}", config);
     }
-}
\ No newline at end of file
+
+    #[test]
+    pub fn validate_store_prefix_works() {
+        assert!(validate_store_prefix("ibc").is_ok());
+
+        assert!(validate_store_prefix("").is_err());
+        assert!(validate_store_prefix("ibc/key").is_err());
+        assert!(validate_store_prefix(" ibc").is_err());
+    }
+
+    #[test]
+    pub fn load_empty_store_prefix_fails() {
+        let mut config = test_chain_config();
+        config.store_prefix = String::new();
+        let file_path = write_test_chain_config("empty_store_prefix", &config);
+
+        let result = load_cosmos_chain_config(file_path.to_str().unwrap());
+        std::fs::remove_file(&file_path).unwrap();
+
+        match result {
+            Err(e) => assert!(matches!(e.detail(), ErrorDetail::InvalidStorePrefix(_))),
+            Ok(_) => panic!("an empty store prefix should be rejected"),
+        }
+    }
+
+    #[test]
+    pub fn load_mnemonic_without_account_prefix_fails() {
+        let mut config = test_chain_config();
+        config.mnemonic_env = Some("CHAIN_A_MNEMONIC".to_string());
+        let file_path = write_test_chain_config("mnemonic_without_account_prefix", &config);
+
+        let result = load_cosmos_chain_config(file_path.to_str().unwrap());
+        std::fs::remove_file(&file_path).unwrap();
+
+        match result {
+            Err(e) => assert!(matches!(e.detail(), ErrorDetail::MissingAccountPrefix(_))),
+            Ok(_) => panic!("a mnemonic key without account_prefix should be rejected"),
+        }
+
+        config.account_prefix = Some("cosmos".to_string());
+        assert!(validate_account_prefix(&config).is_ok());
+    }
+
+    #[test]
+    pub fn eth_key_without_account_prefix_fails() {
+        let mut config = test_chain_config();
+        assert!(validate_account_prefix(&config).is_ok());
+
+        config.key_type = KeyType::EthSecp256k1;
+        match validate_account_prefix(&config) {
+            Err(e) => assert!(matches!(e.detail(), ErrorDetail::MissingAccountPrefix(_))),
+            Ok(_) => panic!("an eth_secp256k1 key without account_prefix should be rejected"),
+        }
+
+        config.account_prefix = Some("evmos".to_string());
+        assert!(validate_account_prefix(&config).is_ok());
+    }
+}
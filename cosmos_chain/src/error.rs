@@ -1,12 +1,13 @@
 use flex_error::{define_error, TraceError, DisplayOnly};
 use tonic::{transport::Error as TransportError, Status as GrpcStatus};
 use prost::{DecodeError, EncodeError};
-use std::io::Error as IOError;
+use std::{env::VarError, io::Error as IOError, time::Duration};
 use utils::file::error::FileError;
+use tendermint::{block::Height, Error as TendermintError};
 use tendermint_rpc::error::Error as TrpcError;
 use serde_json::Error as SerdeJsonError;
 use utils::encode::error::EncodeError as UtilsEncodeError;
-use crate::tx::types::MEMO_MAX_LEN;
+use crate::tx::types::{TxFailure, MEMO_MAX_LEN};
 
 define_error! {
     Error {
@@ -19,6 +20,9 @@ define_error! {
         GrpcTransport
             [ TraceError<TransportError> ]
             |_| { "error in underlying transport when making gRPC call" },
+        GrpcDecodeLimit
+            { query: String, limit: usize }
+            |e| { format!("gRPC call `{}` response exceeds the max decoding size of {} bytes, raise `max_grpc_decoding_size`", e.query, e.limit) },
         Trpc
             { payload_type: String }
             [ TraceError<TrpcError> ]
@@ -41,6 +45,12 @@ define_error! {
         LoadCosmosChainConifg
             [ TraceError<FileError> ]
             |_| { "Load cosmos chain config error" },
+        InvalidStorePrefix
+            { store_prefix: String }
+            |e| { format!("invalid store prefix `{}`, it must be a non-empty store name such as `ibc`", e.store_prefix) },
+        MissingAccountPrefix
+            { chain_id: String }
+            |e| { format!("chain {} loads its key from a mnemonic or uses eth_secp256k1 keys, account_prefix must be configured", e.chain_id) },
         EmptyGrpcClient
             |_| { "empty cosmos grpc client" },
         EmptyTendermintRpcClient
@@ -55,6 +65,25 @@ define_error! {
         LatestBlockResults
             [ TraceError<TrpcError> ]
             |_| { "query latest block results error" },
+        AbciQuery
+            [ TraceError<TrpcError> ]
+            |_| { "abci query error" },
+        AbciQueryResponse
+            { path: String, log: String }
+            |e| { format!("abci query `{}` returned an error: {}", e.path, e.log) },
+        InvalidSequenceBytes
+            { path: String, length: usize }
+            |e| { format!("value of `{}` is {} bytes long, expected an 8 bytes sequence", e.path, e.length) },
+        EmptyResponseProof
+            { path: String }
+            |e| { format!("abci query `{}` returned no proof", e.path) },
+        HeightWaitTimeout
+            { target_height: Height, timeout: Duration }
+            |e| { format!("timed out after {:?} waiting for height {}", e.timeout, e.target_height) },
+        RetryExhausted
+            { operation: String, attempts: u32 }
+            [ DisplayOnly<Error> ]
+            |e| { format!("{} failed after {} attempts", e.operation, e.attempts) },
 
         // keyring error
         EncodedPublicKey
@@ -71,6 +100,10 @@ define_error! {
             { address_bytes: Vec<u8> }
             [ TraceError<UtilsEncodeError> ]
             |e| { format!("address {:?} bech32 encode error", e.address_bytes) },
+        MnemonicEnv
+            { env_var: String }
+            [ TraceError<VarError> ]
+            |e| { format!("cannot read the mnemonic from environment variable {}", e.env_var) },
         InvalidMnemonic
             [ DisplayOnly<anyhow::Error> ]
             |_| { "invalid mnemonic" },
@@ -93,14 +126,40 @@ define_error! {
         EmptyKeyPair
             |_| { "empty key pair" },
 
+        // client
+        InvalidTrustLevel
+            { numerator: u64, denominator: u64 }
+            |e| { format!("invalid trust level {}/{}, it must be within [1/3, 1]", e.numerator, e.denominator) },
+        MaxClockDriftTooSmall
+            { max_clock_drift: Duration, min_clock_drift: Duration }
+            |e| { format!("max clock drift {:?} is below {:?}, valid headers would be rejected as in the future", e.max_clock_drift, e.min_clock_drift) },
+        InvalidTrustingPeriod
+            { trusting_period: Duration, unbonding_period: Duration, fraction: f64 }
+            |e| { format!("trusting period {:?} exceeds {} of the unbonding period {:?}", e.trusting_period, e.fraction, e.unbonding_period) },
+
         // estimate
         TxProtobufEncode
             [ TraceError<UtilsEncodeError> ]
             |_| { "tx protobuf encode error" },
+        CheckTx
+            { tx_hash: String, failure: TxFailure }
+            |e| { format!("tx {} was rejected by CheckTx: {}", e.tx_hash, e.failure) },
+        DeliverTx
+            { tx_hash: String, failure: TxFailure }
+            |e| { format!("tx {} failed in DeliverTx: {}", e.tx_hash, e.failure) },
+        TxConfirmationTimeout
+            { tx_hash: String, timeout: Duration }
+            |e| { format!("tx {} was not committed within {:?}", e.tx_hash, e.timeout) },
         SimulateTxGas
             |_| { "tx simulation no gas amount used was retured" },
+        SimulateEventDecode
+            [ TraceError<TendermintError> ]
+            |_| { "decode tx simulation event error" },
         TxSign
-            |e| { "tx signature error" }
+            |e| { "tx signature error" },
+        TxMemo
+            [ MemoError ]
+            |_| { "invalid tx memo" }
     }
 }
 
@@ -3,6 +3,7 @@ use std::str::FromStr;
 use bip39::{Language, Mnemonic, Seed};
 use bitcoin::{
     bip32::{ChildNumber, DerivationPath, ExtendedPrivKey, ExtendedPubKey},
+    hashes::{hash160, Hash},
     secp256k1::Secp256k1,
     Network,
 };
@@ -11,6 +12,7 @@ use hdpath::StandardHDPath;
 use secp256k1::{SecretKey, PublicKey, Message, ecdsa::Signature};
 use serde::{Deserialize, Deserializer, Serialize};
 use sha2::Sha256;
+use sha3::Keccak256;
 use digest::Digest;
 use subtle_encoding::base64;
 use utils::encode::{bech32, protobuf};
@@ -104,10 +106,60 @@ pub fn encode_bech32_address(address_prefix: &str, address_bytes: &[u8]) -> Resu
         .map_err(|e| Error::address_bech32_encode(address_bytes.to_vec(), e))
 }
 
+/// Signature algorithm of an account. EVM-compatible chains such as Evmos use
+/// `ethsecp256k1`, which hashes with Keccak256 instead of SHA256.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyType {
+    #[default]
+    Secp256k1,
+    EthSecp256k1,
+}
+
+impl KeyType {
+    pub fn public_key_type_url(&self) -> &'static str {
+        match self {
+            KeyType::Secp256k1 => "/cosmos.crypto.secp256k1.PubKey",
+            KeyType::EthSecp256k1 => "/ethermint.crypto.v1.ethsecp256k1.PubKey",
+        }
+    }
+
+    // Key files record the public key type url, e.g. `/ethermint.crypto.v1.ethsecp256k1.PubKey`
+    pub fn from_public_key_type_url(type_url: &str) -> Self {
+        if type_url.contains("ethsecp256k1") {
+            KeyType::EthSecp256k1
+        } else {
+            KeyType::Secp256k1
+        }
+    }
+
+    fn digest(&self, message: &[u8]) -> Vec<u8> {
+        match self {
+            KeyType::Secp256k1 => Sha256::digest(message).to_vec(),
+            KeyType::EthSecp256k1 => Keccak256::digest(message).to_vec(),
+        }
+    }
+}
+
+// Cosmos SDK secp256k1 addresses are RIPEMD160(SHA256(compressed public key)),
+// ethsecp256k1 addresses are the last 20 bytes of Keccak256(uncompressed public key)
+pub fn address_bytes_from_public_key(public_key: &PublicKey, key_type: KeyType) -> Vec<u8> {
+    match key_type {
+        KeyType::Secp256k1 => hash160::Hash::hash(&public_key.serialize())
+            .to_byte_array()
+            .to_vec(),
+        KeyType::EthSecp256k1 => {
+            let uncompressed = public_key.serialize_uncompressed();
+            Keccak256::digest(&uncompressed[1..])[12..].to_vec()
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Secp256k1KeyPair {
     pub public_key: PublicKey,
     private_key: SecretKey,
+    pub key_type: KeyType,
 }
 
 impl Secp256k1KeyPair {
@@ -118,15 +170,21 @@ impl Secp256k1KeyPair {
         Ok(Self {
             public_key: publick_key.public_key,
             private_key: private_key.private_key,
+            key_type: KeyType::default(),
         })
     }
 
+    pub fn with_key_type(mut self, key_type: KeyType) -> Self {
+        self.key_type = key_type;
+        self
+    }
+
     pub fn public_key_bytes(&self) -> Result<Vec<u8>, Error> {
         protobuf::encode_to_bytes(&self.public_key.serialize().to_vec()).map_err(|e| Error::utils_protobuf_encode("secp256l1 public key".to_string(), e))
     }
 
     pub fn sign(&self, message: &[u8]) -> Result<Vec<u8>, Error> {
-        let message_hash = Sha256::digest(message);
+        let message_hash = self.key_type.digest(message);
         let message = Message::from_slice(&message_hash).unwrap();
         let signature = Secp256k1::signing_only().sign_ecdsa(&message, &self.private_key).serialize_compact().to_vec();
 
@@ -134,7 +192,7 @@ impl Secp256k1KeyPair {
     }
 
     pub fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
-        let message_hash = Sha256::digest(message);
+        let message_hash = self.key_type.digest(message);
         let message = Message::from_slice(&message_hash).unwrap();
 
         let signature = Signature::from_compact(signature).expect("signature convert error");
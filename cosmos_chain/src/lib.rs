@@ -1,6 +1,7 @@
 pub mod query {
     pub mod grpc;
     pub mod trpc;
+    pub mod path;
     pub mod types;
 }
 pub mod tx;
@@ -10,4 +11,5 @@ pub mod config;
 pub mod connection;
 pub mod client;
 pub mod keyring;
-pub mod account;
\ No newline at end of file
+pub mod account;
+pub mod retry;
\ No newline at end of file
@@ -1,13 +1,21 @@
 use http::Uri;
-use ibc_proto::cosmos::auth::v1beta1::{
-    query_client::QueryClient, BaseAccount, EthAccount, QueryAccountRequest, QueryAccountsRequest,
+use ibc_proto::{
+    cosmos::auth::v1beta1::{
+        query_client::QueryClient, BaseAccount, EthAccount, QueryAccountRequest,
+        QueryAccountsRequest,
+    },
+    google::protobuf::Any,
 };
-use log::info;
+use tracing::info;
 use prost::Message;
 use tonic::{codegen::ok, transport::Channel};
 
-use crate::{config::default::max_grpc_decoding_size, error::Error};
-use tracing::info as tracing_info;
+use crate::{
+    config::default::max_grpc_decoding_size,
+    error::{Error, ErrorDetail},
+};
+
+use super::pagination::{collect_pages, page_request, MAX_PAGES};
 
 pub async fn query_detail_account(
     grpc_client: &mut QueryClient<Channel>,
@@ -37,16 +45,21 @@ pub async fn query_detail_account(
         None => return Err(Error::empty_query_account(account_address.to_string())),
     };
 
-    if account_resp.type_url == "/cosmos.auth.v1beta1.BaseAccount" {
-        Ok(BaseAccount::decode(account_resp.value.as_slice())
+    decode_base_account(&account_resp)
+}
+
+// Chains built on ethermint (e.g. Evmos) return an EthAccount wrapping the BaseAccount
+pub fn decode_base_account(account: &Any) -> Result<BaseAccount, Error> {
+    if account.type_url == "/cosmos.auth.v1beta1.BaseAccount" {
+        Ok(BaseAccount::decode(account.value.as_slice())
             .map_err(|e| Error::protobuf_decode("BaseAccount".to_string(), e))?)
-    } else if account_resp.type_url.ends_with(".EthAccount") {
-        Ok(EthAccount::decode(account_resp.value.as_slice())
+    } else if account.type_url.ends_with(".EthAccount") {
+        Ok(EthAccount::decode(account.value.as_slice())
             .map_err(|e| Error::protobuf_decode("EthAccount".to_string(), e))?
             .base_account
             .ok_or_else(Error::empty_base_account)?)
     } else {
-        Err(Error::unknown_account_type(account_resp.type_url))
+        Err(Error::unknown_account_type(account.type_url.clone()))
     }
 }
 
@@ -54,30 +67,43 @@ pub async fn query_detail_account(
 pub async fn query_all_account(
     grpc_client: &mut QueryClient<Channel>,
 ) -> Result<Vec<BaseAccount>, Error> {
-    tracing_info!("query all account access");
-    let request_all = tonic::Request::new(QueryAccountsRequest { pagination: None });
-    let reponse = grpc_client.accounts(request_all).await;
+    info!("query all account access");
+    let pages = collect_pages(
+        "query_accounts",
+        MAX_PAGES,
+        |key| {
+            let mut grpc_client = grpc_client.clone();
+            let request_all = tonic::Request::new(QueryAccountsRequest {
+                pagination: page_request(key),
+            });
 
-    //info!("{:?}", reponse);
+            async move {
+                grpc_client
+                    .accounts(request_all)
+                    .await
+                    .map(|r| r.into_inner())
+                    .map_err(|e| Error::grpc_status(e, "query_accounts".to_owned()))
+            }
+        },
+        |page| {
+            page.pagination
+                .as_ref()
+                .map(|p| p.next_key.clone())
+                .unwrap_or_default()
+        },
+    )
+    .await?;
 
-    let accounts_resp = reponse
-        .map_err(|e| Error::grpc_status(e, "query_accounts".to_owned()))?
-        .into_inner()
-        .accounts;
+    let accounts_resp = pages.into_iter().flat_map(|page| page.accounts);
 
     let mut base_accounts: Vec<BaseAccount> = vec![];
 
     for account in accounts_resp {
-        if account.type_url == "/cosmos.auth.v1beta1.BaseAccount" {
-            let ba = BaseAccount::decode(account.value.as_slice())
-                .map_err(|e| Error::protobuf_decode("BaseAccount".to_string(), e))?;
-            base_accounts.push(ba);
-        } else if account.type_url.ends_with(".EthAccount") {
-            let ba = EthAccount::decode(account.value.as_slice())
-                .map_err(|e| Error::protobuf_decode("EthAccount".to_string(), e))?
-                .base_account
-                .ok_or_else(Error::empty_base_account)?;
-            base_accounts.push(ba);
+        // Module and vesting accounts are skipped
+        match decode_base_account(&account) {
+            Ok(ba) => base_accounts.push(ba),
+            Err(e) if matches!(e.detail(), ErrorDetail::UnknownAccountType(_)) => {}
+            Err(e) => return Err(e),
         }
     }
 
@@ -87,3 +113,202 @@ pub async fn query_all_account(
 
     Err(Error::no_accounts())
 }
+
+#[cfg(test)]
+pub mod account_tests {
+    use ibc_proto::{
+        cosmos::{
+            auth::v1beta1::{
+                query_client::QueryClient,
+                query_server::{Query, QueryServer},
+                AddressBytesToStringRequest, AddressBytesToStringResponse,
+                AddressStringToBytesRequest, AddressStringToBytesResponse, BaseAccount,
+                Bech32PrefixRequest, Bech32PrefixResponse, EthAccount, ModuleAccount,
+                QueryAccountAddressByIdRequest, QueryAccountAddressByIdResponse,
+                QueryAccountRequest, QueryAccountResponse, QueryAccountsRequest,
+                QueryAccountsResponse, QueryModuleAccountByNameRequest,
+                QueryModuleAccountByNameResponse, QueryModuleAccountsRequest,
+                QueryModuleAccountsResponse, QueryParamsRequest, QueryParamsResponse,
+            },
+            base::query::v1beta1::PageResponse,
+        },
+        google::protobuf::Any,
+    };
+    use prost::Message;
+    use tokio::net::TcpListener;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tonic::{transport::Server, Request, Response, Status};
+
+    use super::{decode_base_account, query_all_account};
+
+    const MOCK_PAGE_SIZE: usize = 2;
+
+    fn base_account(address: &str, account_number: u64) -> BaseAccount {
+        BaseAccount {
+            address: address.to_string(),
+            pub_key: None,
+            account_number,
+            sequence: 0,
+        }
+    }
+
+    fn any(type_url: &str, value: Vec<u8>) -> Any {
+        Any {
+            type_url: type_url.to_string(),
+            value,
+        }
+    }
+
+    // Auth query service serving `accounts` in pages of `MOCK_PAGE_SIZE`, the next key being the page index
+    struct MockAuthQuery {
+        accounts: Vec<Any>,
+    }
+
+    #[tonic::async_trait]
+    impl Query for MockAuthQuery {
+        async fn accounts(
+            &self,
+            request: Request<QueryAccountsRequest>,
+        ) -> Result<Response<QueryAccountsResponse>, Status> {
+            let key = request.into_inner().pagination.unwrap_or_default().key;
+            let page = key.first().copied().unwrap_or_default() as usize;
+
+            let chunks: Vec<&[Any]> = self.accounts.chunks(MOCK_PAGE_SIZE).collect();
+            let next_key = if page + 1 < chunks.len() {
+                vec![page as u8 + 1]
+            } else {
+                vec![]
+            };
+
+            Ok(Response::new(QueryAccountsResponse {
+                accounts: chunks.get(page).map(|chunk| chunk.to_vec()).unwrap_or_default(),
+                pagination: Some(PageResponse { next_key, total: 0 }),
+            }))
+        }
+
+        async fn account(
+            &self,
+            _: Request<QueryAccountRequest>,
+        ) -> Result<Response<QueryAccountResponse>, Status> {
+            Err(Status::unimplemented("account"))
+        }
+
+        async fn account_address_by_id(
+            &self,
+            _: Request<QueryAccountAddressByIdRequest>,
+        ) -> Result<Response<QueryAccountAddressByIdResponse>, Status> {
+            Err(Status::unimplemented("account_address_by_id"))
+        }
+
+        async fn params(
+            &self,
+            _: Request<QueryParamsRequest>,
+        ) -> Result<Response<QueryParamsResponse>, Status> {
+            Err(Status::unimplemented("params"))
+        }
+
+        async fn module_accounts(
+            &self,
+            _: Request<QueryModuleAccountsRequest>,
+        ) -> Result<Response<QueryModuleAccountsResponse>, Status> {
+            Err(Status::unimplemented("module_accounts"))
+        }
+
+        async fn module_account_by_name(
+            &self,
+            _: Request<QueryModuleAccountByNameRequest>,
+        ) -> Result<Response<QueryModuleAccountByNameResponse>, Status> {
+            Err(Status::unimplemented("module_account_by_name"))
+        }
+
+        async fn bech32_prefix(
+            &self,
+            _: Request<Bech32PrefixRequest>,
+        ) -> Result<Response<Bech32PrefixResponse>, Status> {
+            Err(Status::unimplemented("bech32_prefix"))
+        }
+
+        async fn address_bytes_to_string(
+            &self,
+            _: Request<AddressBytesToStringRequest>,
+        ) -> Result<Response<AddressBytesToStringResponse>, Status> {
+            Err(Status::unimplemented("address_bytes_to_string"))
+        }
+
+        async fn address_string_to_bytes(
+            &self,
+            _: Request<AddressStringToBytesRequest>,
+        ) -> Result<Response<AddressStringToBytesResponse>, Status> {
+            Err(Status::unimplemented("address_string_to_bytes"))
+        }
+    }
+
+    #[actix_rt::test]
+    pub async fn query_all_account_works() {
+        let eth_account = EthAccount {
+            base_account: Some(base_account("evmos1second", 2)),
+            code_hash: vec![],
+        };
+        let module_account = ModuleAccount {
+            base_account: Some(base_account("cosmos1module", 3)),
+            name: "transfer".to_string(),
+            permissions: vec![],
+        };
+        let accounts = vec![
+            any("/cosmos.auth.v1beta1.BaseAccount", base_account("cosmos1first", 1).encode_to_vec()),
+            any("/ethermint.types.v1.EthAccount", eth_account.encode_to_vec()),
+            any("/cosmos.auth.v1beta1.ModuleAccount", module_account.encode_to_vec()),
+            any("/cosmos.auth.v1beta1.BaseAccount", base_account("cosmos1fourth", 4).encode_to_vec()),
+        ];
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            Server::builder()
+                .add_service(QueryServer::new(MockAuthQuery { accounts }))
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+        let mut grpc_client = QueryClient::connect(format!("http://{}", addr)).await.unwrap();
+
+        let accounts = query_all_account(&mut grpc_client).await.unwrap();
+
+        // Both pages are decoded in order, the module account is skipped
+        let addresses: Vec<&str> = accounts.iter().map(|account| account.address.as_str()).collect();
+        assert_eq!(addresses, vec!["cosmos1first", "evmos1second", "cosmos1fourth"]);
+        assert_eq!(accounts[1].account_number, 2);
+    }
+
+    #[test]
+    pub fn decode_base_account_works() {
+        let account = base_account("cosmos1first", 7);
+
+        assert_eq!(
+            decode_base_account(&any("/cosmos.auth.v1beta1.BaseAccount", account.encode_to_vec())).unwrap(),
+            account
+        );
+    }
+
+    #[test]
+    pub fn decode_eth_account_works() {
+        let eth_account = EthAccount {
+            base_account: Some(base_account("evmos1npvwllfr9dqr8erajqqr6s0vxnk2ak55re90dz", 7)),
+            // Keccak256 of empty code
+            code_hash: vec![
+                0xc5, 0xd2, 0x46, 0x01, 0x86, 0xf7, 0x23, 0x3c, 0x92, 0x7e, 0x7d, 0xb2, 0xdc, 0xc7,
+                0x03, 0xc0, 0xe5, 0x00, 0xb6, 0x53, 0xca, 0x82, 0x27, 0x3b, 0x7b, 0xfa, 0xd8, 0x04,
+                0x5d, 0x85, 0xa4, 0x70,
+            ],
+        };
+
+        let decoded =
+            decode_base_account(&any("/ethermint.types.v1.EthAccount", eth_account.encode_to_vec())).unwrap();
+        assert_eq!(decoded.address, "evmos1npvwllfr9dqr8erajqqr6s0vxnk2ak55re90dz");
+        assert_eq!(decoded.account_number, 7);
+
+        let empty_eth_account = any("/ethermint.types.v1.EthAccount", EthAccount::default().encode_to_vec());
+        assert!(decode_base_account(&empty_eth_account).is_err());
+
+        let module_account = any("/cosmos.auth.v1beta1.ModuleAccount", vec![]);
+        assert!(decode_base_account(&module_account).is_err());
+    }
+}
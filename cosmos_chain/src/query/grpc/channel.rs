@@ -0,0 +1,397 @@
+use std::cell::Cell;
+
+use ibc_proto::ibc::core::{
+    channel::v1::{
+        query_client::QueryClient as ChannelQueryClient, QueryPacketCommitmentsRequest,
+        QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest,
+    },
+    client::v1::Height,
+};
+use tonic::{metadata::MetadataValue, transport::Channel};
+
+use crate::error::Error;
+
+use super::pagination::{collect_pages, page_request, BLOCK_HEIGHT_HEADER, MAX_PAGES};
+
+// Returns the subset of the given acknowledgement sequences that the chain has not yet processed
+pub async fn query_unreceived_acks(
+    grpc_client: &mut ChannelQueryClient<Channel>,
+    port_id: &str,
+    channel_id: &str,
+    sequences: &[u64],
+) -> Result<Vec<u64>, Error> {
+    let request = tonic::Request::new(QueryUnreceivedAcksRequest {
+        port_id: port_id.to_string(),
+        channel_id: channel_id.to_string(),
+        packet_ack_sequences: sequences.to_vec(),
+    });
+
+    let response = grpc_client
+        .unreceived_acks(request)
+        .await
+        .map_err(|e| Error::grpc_status(e, "query_unreceived_acks".to_owned()))?
+        .into_inner();
+
+    Ok(response.sequences)
+}
+
+// Returns the subset of the given packet sequences that the chain has not yet received
+pub async fn query_unreceived_packets(
+    grpc_client: &mut ChannelQueryClient<Channel>,
+    port_id: &str,
+    channel_id: &str,
+    sequences: &[u64],
+) -> Result<Vec<u64>, Error> {
+    let request = tonic::Request::new(QueryUnreceivedPacketsRequest {
+        port_id: port_id.to_string(),
+        channel_id: channel_id.to_string(),
+        packet_commitment_sequences: sequences.to_vec(),
+    });
+
+    let response = grpc_client
+        .unreceived_packets(request)
+        .await
+        .map_err(|e| Error::grpc_status(e, "query_unreceived_packets".to_owned()))?
+        .into_inner();
+
+    Ok(response.sequences)
+}
+
+// Returns the sequences of all outstanding packet commitments on the channel and the height they were queried at.
+// The pages after the first one are queried at the height of the first page, so that the commitments
+// are consistent with the returned height even if the chain moves on while paging.
+pub async fn query_packet_commitments(
+    grpc_client: &mut ChannelQueryClient<Channel>,
+    port_id: &str,
+    channel_id: &str,
+) -> Result<(Vec<u64>, Option<Height>), Error> {
+    let query_height: Cell<Option<u64>> = Cell::new(None);
+
+    let pages = collect_pages(
+        "query_packet_commitments",
+        MAX_PAGES,
+        |key| {
+            let mut grpc_client = grpc_client.clone();
+            let mut request = tonic::Request::new(QueryPacketCommitmentsRequest {
+                port_id: port_id.to_string(),
+                channel_id: channel_id.to_string(),
+                pagination: page_request(key),
+            });
+            if let Some(height) = query_height.get() {
+                request
+                    .metadata_mut()
+                    .insert(BLOCK_HEIGHT_HEADER, MetadataValue::from(height));
+            }
+
+            async move {
+                grpc_client
+                    .packet_commitments(request)
+                    .await
+                    .map(|r| r.into_inner())
+                    .map_err(|e| Error::grpc_status(e, "query_packet_commitments".to_owned()))
+            }
+        },
+        |page| {
+            if query_height.get().is_none() {
+                query_height.set(page.height.as_ref().map(|h| h.revision_height));
+            }
+
+            page.pagination
+                .as_ref()
+                .map(|p| p.next_key.clone())
+                .unwrap_or_default()
+        },
+    )
+    .await?;
+
+    let height = pages.first().and_then(|page| page.height.clone());
+    let sequences = pages
+        .iter()
+        .flat_map(|page| page.commitments.iter().map(|commitment| commitment.sequence))
+        .collect();
+
+    Ok((sequences, height))
+}
+
+#[cfg(test)]
+pub mod channel_tests {
+    use std::{
+        collections::HashSet,
+        net::SocketAddr,
+        sync::{
+            atomic::{AtomicU64, AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use ibc_proto::{cosmos::base::query::v1beta1::PageResponse, ibc::core::client::v1::Height};
+
+    use ibc_proto::ibc::core::channel::v1::{
+        query_client::QueryClient as ChannelQueryClient,
+        query_server::{Query, QueryServer},
+        QueryChannelClientStateRequest, QueryChannelClientStateResponse,
+        QueryChannelConsensusStateRequest, QueryChannelConsensusStateResponse, QueryChannelRequest,
+        QueryChannelResponse, QueryChannelsRequest, QueryChannelsResponse,
+        QueryConnectionChannelsRequest, QueryConnectionChannelsResponse,
+        QueryNextSequenceReceiveRequest, QueryNextSequenceReceiveResponse,
+        QueryPacketAcknowledgementRequest, QueryPacketAcknowledgementResponse,
+        QueryPacketAcknowledgementsRequest, QueryPacketAcknowledgementsResponse,
+        QueryPacketCommitmentRequest, QueryPacketCommitmentResponse, QueryPacketCommitmentsRequest,
+        QueryPacketCommitmentsResponse, QueryPacketReceiptRequest, QueryPacketReceiptResponse,
+        QueryUnreceivedAcksRequest, QueryUnreceivedAcksResponse, QueryUnreceivedPacketsRequest,
+        QueryUnreceivedPacketsResponse, PacketState,
+    };
+    use tokio::net::TcpListener;
+    use tokio_stream::{wrappers::TcpListenerStream, StreamExt};
+    use tonic::{
+        transport::{Channel, Server},
+        Request, Response, Status,
+    };
+
+    use crate::error::ErrorDetail;
+
+    use crate::query::grpc::pagination::BLOCK_HEIGHT_HEADER;
+
+    use super::{query_packet_commitments, query_unreceived_acks};
+
+    const MOCK_PAGE_SIZE: usize = 2;
+
+    // Channel query service of a chain on which the packets in `pending` still have
+    // their commitment stored, i.e. their acknowledgement has not been received yet
+    pub struct MockChannelQuery {
+        pub pending: HashSet<u64>,
+        // Advances with every query that is not pinned to a height
+        latest_height: AtomicU64,
+    }
+
+    impl MockChannelQuery {
+        pub fn new(pending: impl IntoIterator<Item = u64>) -> Self {
+            Self {
+                pending: pending.into_iter().collect(),
+                latest_height: AtomicU64::new(100),
+            }
+        }
+
+        // The height the query is served at, the pinned one if the request carries it
+        fn query_height<T>(&self, request: &Request<T>) -> u64 {
+            match request.metadata().get(BLOCK_HEIGHT_HEADER) {
+                Some(height) => height.to_str().unwrap().parse().unwrap(),
+                None => self.latest_height.fetch_add(1, Ordering::SeqCst),
+            }
+        }
+    }
+
+    #[tonic::async_trait]
+    impl Query for MockChannelQuery {
+        async fn unreceived_acks(
+            &self,
+            request: Request<QueryUnreceivedAcksRequest>,
+        ) -> Result<Response<QueryUnreceivedAcksResponse>, Status> {
+            let request = request.into_inner();
+            if request.port_id != "transfer" || request.channel_id != "channel-0" {
+                return Err(Status::not_found("channel not found"));
+            }
+
+            let sequences = request
+                .packet_ack_sequences
+                .into_iter()
+                .filter(|sequence| self.pending.contains(sequence))
+                .collect();
+
+            Ok(Response::new(QueryUnreceivedAcksResponse {
+                sequences,
+                height: None,
+            }))
+        }
+
+        async fn channel(
+            &self,
+            _: Request<QueryChannelRequest>,
+        ) -> Result<Response<QueryChannelResponse>, Status> {
+            Err(Status::unimplemented("channel"))
+        }
+
+        async fn channels(
+            &self,
+            _: Request<QueryChannelsRequest>,
+        ) -> Result<Response<QueryChannelsResponse>, Status> {
+            Err(Status::unimplemented("channels"))
+        }
+
+        async fn connection_channels(
+            &self,
+            _: Request<QueryConnectionChannelsRequest>,
+        ) -> Result<Response<QueryConnectionChannelsResponse>, Status> {
+            Err(Status::unimplemented("connection_channels"))
+        }
+
+        async fn channel_client_state(
+            &self,
+            _: Request<QueryChannelClientStateRequest>,
+        ) -> Result<Response<QueryChannelClientStateResponse>, Status> {
+            Err(Status::unimplemented("channel_client_state"))
+        }
+
+        async fn channel_consensus_state(
+            &self,
+            _: Request<QueryChannelConsensusStateRequest>,
+        ) -> Result<Response<QueryChannelConsensusStateResponse>, Status> {
+            Err(Status::unimplemented("channel_consensus_state"))
+        }
+
+        async fn packet_commitment(
+            &self,
+            _: Request<QueryPacketCommitmentRequest>,
+        ) -> Result<Response<QueryPacketCommitmentResponse>, Status> {
+            Err(Status::unimplemented("packet_commitment"))
+        }
+
+        // Serves the pending commitments in pages of `MOCK_PAGE_SIZE`, the next key being the page index
+        async fn packet_commitments(
+            &self,
+            request: Request<QueryPacketCommitmentsRequest>,
+        ) -> Result<Response<QueryPacketCommitmentsResponse>, Status> {
+            let height = self.query_height(&request);
+            let pinned = request.metadata().contains_key(BLOCK_HEIGHT_HEADER);
+            let key = request.into_inner().pagination.unwrap_or_default().key;
+            if !key.is_empty() && !pinned {
+                return Err(Status::failed_precondition("page queried at a different height"));
+            }
+            let page = key.first().copied().unwrap_or_default() as usize;
+
+            let mut sequences: Vec<u64> = self.pending.iter().copied().collect();
+            sequences.sort();
+            let chunks: Vec<&[u64]> = sequences.chunks(MOCK_PAGE_SIZE).collect();
+            let next_key = if page + 1 < chunks.len() {
+                vec![page as u8 + 1]
+            } else {
+                vec![]
+            };
+
+            Ok(Response::new(QueryPacketCommitmentsResponse {
+                commitments: chunks
+                    .get(page)
+                    .map(|chunk| {
+                        chunk
+                            .iter()
+                            .map(|sequence| PacketState {
+                                port_id: "transfer".to_string(),
+                                channel_id: "channel-0".to_string(),
+                                sequence: *sequence,
+                                data: vec![1],
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                pagination: Some(PageResponse { next_key, total: 0 }),
+                height: Some(Height {
+                    revision_number: 0,
+                    revision_height: height,
+                }),
+            }))
+        }
+
+        async fn packet_receipt(
+            &self,
+            _: Request<QueryPacketReceiptRequest>,
+        ) -> Result<Response<QueryPacketReceiptResponse>, Status> {
+            Err(Status::unimplemented("packet_receipt"))
+        }
+
+        async fn packet_acknowledgement(
+            &self,
+            _: Request<QueryPacketAcknowledgementRequest>,
+        ) -> Result<Response<QueryPacketAcknowledgementResponse>, Status> {
+            Err(Status::unimplemented("packet_acknowledgement"))
+        }
+
+        async fn packet_acknowledgements(
+            &self,
+            _: Request<QueryPacketAcknowledgementsRequest>,
+        ) -> Result<Response<QueryPacketAcknowledgementsResponse>, Status> {
+            Err(Status::unimplemented("packet_acknowledgements"))
+        }
+
+        async fn unreceived_packets(
+            &self,
+            _: Request<QueryUnreceivedPacketsRequest>,
+        ) -> Result<Response<QueryUnreceivedPacketsResponse>, Status> {
+            Err(Status::unimplemented("unreceived_packets"))
+        }
+
+        async fn next_sequence_receive(
+            &self,
+            _: Request<QueryNextSequenceReceiveRequest>,
+        ) -> Result<Response<QueryNextSequenceReceiveResponse>, Status> {
+            Err(Status::unimplemented("next_sequence_receive"))
+        }
+    }
+
+    // Serve `query` on a local port, counting the accepted connections
+    pub async fn serve_mock_channel_query(query: MockChannelQuery) -> (SocketAddr, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let connections = Arc::new(AtomicUsize::new(0));
+
+        let accepted = connections.clone();
+        let incoming = TcpListenerStream::new(listener).map(move |stream| {
+            accepted.fetch_add(1, Ordering::SeqCst);
+            stream
+        });
+        tokio::spawn(
+            Server::builder()
+                .add_service(QueryServer::new(query))
+                .serve_with_incoming(incoming),
+        );
+
+        (addr, connections)
+    }
+
+    async fn mock_channel_query_client(query: MockChannelQuery) -> ChannelQueryClient<Channel> {
+        let (addr, _) = serve_mock_channel_query(query).await;
+
+        ChannelQueryClient::connect(format!("http://{}", addr))
+            .await
+            .unwrap()
+    }
+
+    #[actix_rt::test]
+    pub async fn query_unreceived_acks_works() {
+        let mut grpc_client = mock_channel_query_client(MockChannelQuery::new([2, 4, 7])).await;
+
+        let sequences = query_unreceived_acks(&mut grpc_client, "transfer", "channel-0", &[1, 2, 3, 4, 5])
+            .await
+            .unwrap();
+        assert_eq!(sequences, vec![2, 4]);
+
+        let sequences = query_unreceived_acks(&mut grpc_client, "transfer", "channel-0", &[1, 3])
+            .await
+            .unwrap();
+        assert!(sequences.is_empty());
+
+        let result = query_unreceived_acks(&mut grpc_client, "transfer", "channel-9", &[2]).await;
+        match result {
+            Err(e) => assert!(matches!(e.detail(), ErrorDetail::GrpcStatus(_))),
+            Ok(_) => panic!("an unknown channel should fail the query"),
+        }
+    }
+
+    #[actix_rt::test]
+    pub async fn query_packet_commitments_works() {
+        let mut grpc_client = mock_channel_query_client(MockChannelQuery::new([9, 2, 4, 7, 5])).await;
+
+        let (sequences, height) = query_packet_commitments(&mut grpc_client, "transfer", "channel-0")
+            .await
+            .unwrap();
+
+        assert_eq!(sequences, vec![2, 4, 5, 7, 9]);
+        // The later pages are pinned to the height of the first one
+        assert_eq!(
+            height,
+            Some(Height {
+                revision_number: 0,
+                revision_height: 100,
+            })
+        );
+    }
+}
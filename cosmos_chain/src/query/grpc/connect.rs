@@ -0,0 +1,116 @@
+use ibc_proto::{
+    cosmos::{
+        auth::v1beta1::query_client::QueryClient as AuthQueryClient,
+        tx::v1beta1::service_client::ServiceClient,
+    },
+    ibc::core::channel::v1::query_client::QueryClient as ChannelQueryClient,
+};
+use tracing::info;
+use tonic::{
+    transport::{Channel, Endpoint},
+    Code, Status,
+};
+
+use crate::error::Error;
+
+/// A lazily dialed gRPC channel shared by all the query and service clients of a chain.
+/// The tonic channel is multiplexed and cheap to clone, so every client reuses it
+/// until `reset` drops it after a connection failure.
+#[derive(Debug, Clone)]
+pub struct GrpcConnection {
+    grpc_addr: String,
+    max_decoding_size: usize,
+    channel: Option<Channel>,
+    dials: u64,
+}
+
+impl GrpcConnection {
+    pub fn new(grpc_addr: &str, max_decoding_size: usize) -> Self {
+        Self {
+            grpc_addr: grpc_addr.to_string(),
+            max_decoding_size,
+            channel: None,
+            dials: 0,
+        }
+    }
+
+    /// Maximum size in bytes of a decoded gRPC response.
+    pub fn max_decoding_size(&self) -> usize {
+        self.max_decoding_size
+    }
+
+    pub fn channel(&mut self) -> Result<Channel, Error> {
+        if let Some(channel) = &self.channel {
+            return Ok(channel.clone());
+        }
+
+        let channel = Endpoint::from_shared(self.grpc_addr.clone())
+            .map_err(Error::grpc_transport)?
+            .connect_lazy();
+        self.dials += 1;
+        info!("grpc channel to {} initialized", self.grpc_addr);
+
+        self.channel = Some(channel.clone());
+        Ok(channel)
+    }
+
+    pub fn reset(&mut self) {
+        self.channel = None;
+    }
+
+    /// Number of times the underlying channel has been dialed.
+    pub fn dials(&self) -> u64 {
+        self.dials
+    }
+
+    pub fn auth_query_client(&mut self) -> Result<AuthQueryClient<Channel>, Error> {
+        Ok(AuthQueryClient::new(self.channel()?).max_decoding_message_size(self.max_decoding_size))
+    }
+
+    pub fn channel_query_client(&mut self) -> Result<ChannelQueryClient<Channel>, Error> {
+        Ok(ChannelQueryClient::new(self.channel()?).max_decoding_message_size(self.max_decoding_size))
+    }
+
+    pub fn tx_service_client(&mut self) -> Result<ServiceClient<Channel>, Error> {
+        Ok(ServiceClient::new(self.channel()?).max_decoding_message_size(self.max_decoding_size))
+    }
+}
+
+/// Whether tonic rejected a response for exceeding the max decoding message size.
+pub fn is_decode_limit_exceeded(status: &Status) -> bool {
+    status.code() == Code::OutOfRange && status.message().contains("message length too large")
+}
+
+#[cfg(test)]
+pub mod connect_tests {
+    use tonic::Status;
+
+    use super::{is_decode_limit_exceeded, GrpcConnection};
+
+    #[actix_rt::test]
+    pub async fn grpc_connection_reuse_works() {
+        let mut connection = GrpcConnection::new("http://127.0.0.1:9090", 1024);
+
+        connection.channel().unwrap();
+        connection.channel().unwrap();
+        assert_eq!(connection.dials(), 1);
+
+        connection.reset();
+        connection.channel().unwrap();
+        assert_eq!(connection.dials(), 2);
+    }
+
+    #[actix_rt::test]
+    pub async fn grpc_decode_limit_works() {
+        let mut connection = GrpcConnection::new("http://127.0.0.1:9090", 64 * 1024 * 1024);
+        assert_eq!(connection.max_decoding_size(), 64 * 1024 * 1024);
+        assert!(connection.auth_query_client().is_ok());
+
+        let too_large = Status::out_of_range(
+            "Error, message length too large: found 40000000 bytes, the limit is: 33554432 bytes",
+        );
+        assert!(is_decode_limit_exceeded(&too_large));
+        assert!(!is_decode_limit_exceeded(&Status::out_of_range("height out of range")));
+        assert!(!is_decode_limit_exceeded(&Status::unavailable("message length too large")));
+    }
+}
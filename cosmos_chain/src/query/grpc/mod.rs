@@ -1 +1,4 @@
-pub mod account;
\ No newline at end of file
+pub mod account;
+pub mod channel;
+pub mod connect;
+pub mod pagination;
\ No newline at end of file
@@ -0,0 +1,118 @@
+use std::future::Future;
+
+use ibc_proto::cosmos::base::query::v1beta1::PageRequest;
+use tracing::warn;
+
+use crate::error::Error;
+
+/// Number of entries requested per page.
+pub const PAGE_LIMIT: u64 = 100;
+
+/// Upper bound on the number of pages fetched by a single query.
+pub const MAX_PAGES: usize = 1_000;
+
+/// gRPC metadata key selecting the height a cosmos-sdk query is served at.
+pub const BLOCK_HEIGHT_HEADER: &str = "x-cosmos-block-height";
+
+pub fn page_request(key: Vec<u8>) -> Option<PageRequest> {
+    Some(PageRequest {
+        key,
+        limit: PAGE_LIMIT,
+        ..Default::default()
+    })
+}
+
+// Fetch pages by following the next key cursor until it is empty. Stop after `max_pages`
+// pages and return the partial results, so a node that keeps returning a cursor cannot loop us forever.
+pub async fn collect_pages<R, F, Fut, K>(
+    query: &str,
+    max_pages: usize,
+    mut fetch_page: F,
+    next_key: K,
+) -> Result<Vec<R>, Error>
+where
+    F: FnMut(Vec<u8>) -> Fut,
+    Fut: Future<Output = Result<R, Error>>,
+    K: Fn(&R) -> Vec<u8>,
+{
+    let mut pages = vec![];
+    let mut key = vec![];
+
+    loop {
+        if pages.len() >= max_pages {
+            warn!(
+                "{} stopped after {} pages, returning partial results",
+                query, max_pages
+            );
+            break;
+        }
+
+        let page = fetch_page(key).await?;
+        key = next_key(&page);
+        pages.push(page);
+
+        if key.is_empty() {
+            break;
+        }
+    }
+
+    Ok(pages)
+}
+
+#[cfg(test)]
+pub mod pagination_tests {
+    use std::collections::HashMap;
+
+    use crate::error::Error;
+
+    use super::collect_pages;
+
+    // Mock pages keyed by request cursor: (items, next key)
+    fn mock_pages() -> HashMap<Vec<u8>, (Vec<u64>, Vec<u8>)> {
+        let mut pages = HashMap::new();
+        pages.insert(vec![], (vec![1, 2, 3], vec![1]));
+        pages.insert(vec![1], (vec![4, 5], vec![2]));
+        pages.insert(vec![2], (vec![6], vec![]));
+        pages
+    }
+
+    #[actix_rt::test]
+    pub async fn collect_pages_works() {
+        let pages = mock_pages();
+
+        let result = collect_pages(
+            "mock query",
+            10,
+            |key| {
+                let page = pages.get(&key).cloned();
+                async move { page.ok_or_else(Error::no_accounts) }
+            },
+            |page| page.1.clone(),
+        )
+        .await
+        .unwrap();
+
+        let items: Vec<u64> = result.into_iter().flat_map(|page| page.0).collect();
+        assert_eq!(items, vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[actix_rt::test]
+    pub async fn collect_pages_is_bounded_works() {
+        let pages = mock_pages();
+
+        let result = collect_pages(
+            "mock query",
+            2,
+            |key| {
+                let page = pages.get(&key).cloned();
+                async move { page.ok_or_else(Error::no_accounts) }
+            },
+            |page| page.1.clone(),
+        )
+        .await
+        .unwrap();
+
+        let items: Vec<u64> = result.into_iter().flat_map(|page| page.0).collect();
+        assert_eq!(items, vec![1, 2, 3, 4, 5]);
+    }
+}
@@ -0,0 +1,61 @@
+/// ABCI query path of the IBC store mounted under `store_prefix`,
+/// the key is given as query data.
+pub fn ibc_query_path(store_prefix: &str) -> String {
+    format!("store/{}/key", store_prefix)
+}
+
+pub fn packet_receipt_path(port_id: &str, channel_id: &str, sequence: u64) -> String {
+    format!(
+        "receipts/ports/{}/channels/{}/sequences/{}",
+        port_id, channel_id, sequence
+    )
+}
+
+pub fn next_sequence_send_path(port_id: &str, channel_id: &str) -> String {
+    format!("nextSequenceSend/ports/{}/channels/{}", port_id, channel_id)
+}
+
+pub fn next_sequence_recv_path(port_id: &str, channel_id: &str) -> String {
+    format!("nextSequenceRecv/ports/{}/channels/{}", port_id, channel_id)
+}
+
+pub fn next_sequence_ack_path(port_id: &str, channel_id: &str) -> String {
+    format!("nextSequenceAck/ports/{}/channels/{}", port_id, channel_id)
+}
+
+#[cfg(test)]
+pub mod path_tests {
+    use super::{
+        ibc_query_path, next_sequence_ack_path, next_sequence_recv_path, next_sequence_send_path,
+        packet_receipt_path,
+    };
+
+    #[test]
+    pub fn ibc_query_path_works() {
+        assert_eq!(ibc_query_path("ibc"), "store/ibc/key");
+    }
+
+    #[test]
+    pub fn packet_receipt_path_works() {
+        assert_eq!(
+            packet_receipt_path("transfer", "channel-0", 7),
+            "receipts/ports/transfer/channels/channel-0/sequences/7"
+        );
+    }
+
+    #[test]
+    pub fn next_sequence_path_works() {
+        assert_eq!(
+            next_sequence_send_path("transfer", "channel-0"),
+            "nextSequenceSend/ports/transfer/channels/channel-0"
+        );
+        assert_eq!(
+            next_sequence_recv_path("transfer", "channel-0"),
+            "nextSequenceRecv/ports/transfer/channels/channel-0"
+        );
+        assert_eq!(
+            next_sequence_ack_path("transfer", "channel-0"),
+            "nextSequenceAck/ports/transfer/channels/channel-0"
+        );
+    }
+}
@@ -1,11 +1,74 @@
-use tendermint::abci::response::Info;
-use tendermint_rpc::{ HttpClient, Client };
+use ibc_proto::ibc::core::commitment::v1::MerkleProof;
+use ics23::CommitmentProof;
+use prost::Message;
+use tendermint::{abci::response::Info, merkle::proof::ProofOps};
+use tendermint_rpc::{endpoint::abci_query::AbciQuery, Client, HttpClient};
 
-use crate::error::Error;
+use crate::{error::Error, query::types::QueryHeight};
 
 pub async fn abci_info(trpc: &mut HttpClient) -> Result<Info, Error>{
     let abci_info = trpc.abci_info().await.map_err(|e| Error::abci_info(e))?;
 
     Ok(abci_info)
 
-}
\ No newline at end of file
+}
+
+pub async fn abci_query(
+    trpc: &mut HttpClient,
+    path: String,
+    data: Vec<u8>,
+    height_query: QueryHeight,
+    prove: bool,
+) -> Result<AbciQuery, Error> {
+    let response = trpc
+        .abci_query(Some(path.clone()), data, height_query.into(), prove)
+        .await
+        .map_err(Error::abci_query)?;
+
+    if !response.code.is_ok() {
+        return Err(Error::abci_query_response(path, response.log));
+    }
+
+    if prove && response.proof.is_none() {
+        return Err(Error::empty_response_proof(path));
+    }
+
+    Ok(response)
+}
+
+// Convert the tendermint proof operations into the ics23 merkle proof expected by ibc
+pub fn merkle_proof_from_proof_ops(proof_ops: &ProofOps) -> Result<MerkleProof, Error> {
+    let mut proofs = vec![];
+    for op in proof_ops.ops.iter() {
+        let proof = CommitmentProof::decode(op.data.as_slice())
+            .map_err(|e| Error::protobuf_decode("CommitmentProof".to_string(), e))?;
+        proofs.push(proof);
+    }
+
+    Ok(MerkleProof { proofs })
+}
+
+// Sequences are stored in the IBC store as 8 big-endian bytes
+pub fn decode_sequence(path: &str, value: &[u8]) -> Result<u64, Error> {
+    let bytes: [u8; 8] = value
+        .try_into()
+        .map_err(|_| Error::invalid_sequence_bytes(path.to_string(), value.len()))?;
+
+    Ok(u64::from_be_bytes(bytes))
+}
+
+#[cfg(test)]
+pub mod abci_tests {
+    use super::decode_sequence;
+
+    #[test]
+    pub fn decode_sequence_works() {
+        let path = "nextSequenceRecv/ports/transfer/channels/channel-0";
+
+        assert_eq!(decode_sequence(path, &42_u64.to_be_bytes()).unwrap(), 42);
+        assert_eq!(decode_sequence(path, &[0, 0, 0, 0, 0, 0, 1, 0]).unwrap(), 256);
+
+        assert!(decode_sequence(path, &[]).is_err());
+        assert!(decode_sequence(path, &[1, 2, 3]).is_err());
+    }
+}
@@ -7,6 +7,22 @@ use tendermint::{
 };
 use tendermint_rpc::endpoint::{block_results, block as trpc_block};
 
+/// The height at which a query is performed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryHeight {
+    Latest,
+    Specific(Height),
+}
+
+impl From<QueryHeight> for Option<Height> {
+    fn from(value: QueryHeight) -> Self {
+        match value {
+            QueryHeight::Latest => None,
+            QueryHeight::Specific(height) => Some(height),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Block {
     pub id: Id,
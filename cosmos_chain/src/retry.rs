@@ -0,0 +1,275 @@
+use std::{
+    cmp::min,
+    future::Future,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use tendermint::block::Height;
+use tracing::{debug, warn};
+
+use crate::error::Error;
+
+/// Exponential backoff policy used when polling a node that may be briefly unavailable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    pub base_delay_millis: u64,
+    pub max_delay_millis: u64,
+    pub max_attempts: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            base_delay_millis: 100,
+            max_delay_millis: 5_000,
+            max_attempts: 10,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The delay to wait after the given (zero-based) failed attempt,
+    /// doubling each time and bounded by `max_delay_millis`.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 1_u64.checked_shl(attempt).unwrap_or(u64::MAX);
+        let delay = self.base_delay_millis.saturating_mul(factor);
+
+        Duration::from_millis(min(delay, self.max_delay_millis))
+    }
+}
+
+pub async fn retry_with_backoff<T, F, Fut>(
+    policy: &RetryPolicy,
+    operation: &str,
+    mut f: F,
+) -> Result<T, Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, Error>>,
+{
+    let mut attempt = 0;
+    loop {
+        match f().await {
+            Ok(value) => return Ok(value),
+            Err(e) => {
+                attempt += 1;
+                if attempt >= policy.max_attempts {
+                    return Err(Error::retry_exhausted(operation.to_string(), attempt, e));
+                }
+
+                let delay = policy.delay(attempt - 1);
+                warn!(
+                    "{} failed (attempt {}/{}), retrying in {:?}: {}",
+                    operation, attempt, policy.max_attempts, delay, e
+                );
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+}
+
+/// Call `f` every `poll_interval` until it yields a value, returning `None` once `timeout` expires.
+/// Errors from `f` are logged and treated as not ready yet.
+pub async fn poll_until<T, F, Fut>(
+    timeout: Duration,
+    poll_interval: Duration,
+    mut f: F,
+) -> Option<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Option<T>, Error>>,
+{
+    let deadline = Instant::now() + timeout;
+    loop {
+        match f().await {
+            Ok(Some(value)) => return Some(value),
+            Ok(None) => {}
+            Err(e) => debug!("poll failed, retrying until the deadline: {}", e),
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            return None;
+        }
+
+        tokio::time::sleep(min(poll_interval, deadline - now)).await;
+    }
+}
+
+// Wait until the chain height reaches `target_height`.
+pub async fn wait_for_height_with<F, Fut>(
+    target_height: Height,
+    timeout: Duration,
+    poll_interval: Duration,
+    mut query_height: F,
+) -> Result<Height, Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Height, Error>>,
+{
+    poll_until(timeout, poll_interval, || {
+        let height = query_height();
+        async move {
+            let height = height.await?;
+            Ok((height >= target_height).then_some(height))
+        }
+    })
+    .await
+    .ok_or_else(|| Error::height_wait_timeout(target_height, timeout))
+}
+
+#[cfg(test)]
+pub mod retry_tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    };
+
+    use tendermint::block::Height;
+
+    use crate::error::Error;
+
+    use super::{poll_until, retry_with_backoff, wait_for_height_with, RetryPolicy};
+
+    #[test]
+    pub fn backoff_delay_grows_and_is_bounded() {
+        let policy = RetryPolicy {
+            base_delay_millis: 10,
+            max_delay_millis: 50,
+            max_attempts: 10,
+        };
+
+        assert_eq!(policy.delay(0), Duration::from_millis(10));
+        assert_eq!(policy.delay(1), Duration::from_millis(20));
+        assert_eq!(policy.delay(2), Duration::from_millis(40));
+        assert_eq!(policy.delay(3), Duration::from_millis(50));
+        assert_eq!(policy.delay(64), Duration::from_millis(50));
+    }
+
+    #[test]
+    pub fn partial_retry_policy_works() {
+        let policy: RetryPolicy = serde_json::from_str(r#"{ "max_attempts": 3 }"#).unwrap();
+
+        assert_eq!(
+            policy,
+            RetryPolicy {
+                max_attempts: 3,
+                ..RetryPolicy::default()
+            }
+        );
+    }
+
+    #[actix_rt::test]
+    pub async fn retry_succeeds_after_failures() {
+        let policy = RetryPolicy {
+            base_delay_millis: 5,
+            max_delay_millis: 100,
+            max_attempts: 5,
+        };
+        let calls = Arc::new(Mutex::new(vec![]));
+
+        let result = retry_with_backoff(&policy, "mock query", || {
+            let calls = calls.clone();
+            async move {
+                let mut calls = calls.lock().unwrap();
+                calls.push(Instant::now());
+                if calls.len() <= 3 {
+                    Err(Error::empty_tendermint_rpc_client())
+                } else {
+                    Ok(calls.len())
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 4);
+
+        let calls = calls.lock().unwrap();
+        let gaps: Vec<Duration> = calls.windows(2).map(|w| w[1] - w[0]).collect();
+        assert!(gaps[0] >= Duration::from_millis(5));
+        assert!(gaps[1] >= Duration::from_millis(10));
+        assert!(gaps[2] >= Duration::from_millis(20));
+    }
+
+    #[actix_rt::test]
+    pub async fn retry_gives_up_after_max_attempts() {
+        let policy = RetryPolicy {
+            base_delay_millis: 1,
+            max_delay_millis: 1,
+            max_attempts: 3,
+        };
+        let calls = Arc::new(Mutex::new(0));
+
+        let result: Result<(), Error> = retry_with_backoff(&policy, "mock query", || {
+            let calls = calls.clone();
+            async move {
+                *calls.lock().unwrap() += 1;
+                Err(Error::empty_tendermint_rpc_client())
+            }
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(*calls.lock().unwrap(), 3);
+    }
+
+    #[actix_rt::test]
+    pub async fn wait_for_height_works() {
+        let polls = Arc::new(Mutex::new(0_u32));
+
+        let height = wait_for_height_with(
+            Height::from(10_u32),
+            Duration::from_secs(5),
+            Duration::from_millis(1),
+            || {
+                let polls = polls.clone();
+                async move {
+                    let mut polls = polls.lock().unwrap();
+                    *polls += 1;
+                    Ok(Height::from(7 + *polls))
+                }
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(height, Height::from(10_u32));
+        assert_eq!(*polls.lock().unwrap(), 3);
+    }
+
+    #[actix_rt::test]
+    pub async fn wait_for_height_timeout_works() {
+        let result = wait_for_height_with(
+            Height::from(10_u32),
+            Duration::from_millis(20),
+            Duration::from_millis(5),
+            || async { Ok(Height::from(5_u32)) },
+        )
+        .await;
+
+        assert!(result.is_err());
+    }
+
+    #[actix_rt::test]
+    pub async fn poll_until_skips_errors_works() {
+        let polls = Arc::new(Mutex::new(0_u32));
+
+        let value = poll_until(Duration::from_secs(5), Duration::from_millis(1), || {
+            let polls = polls.clone();
+            async move {
+                let mut polls = polls.lock().unwrap();
+                *polls += 1;
+                match *polls {
+                    1 => Err(Error::empty_tendermint_rpc_client()),
+                    2 => Ok(None),
+                    _ => Ok(Some(*polls)),
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(value, Some(3));
+    }
+}
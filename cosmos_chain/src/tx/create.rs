@@ -7,7 +7,7 @@ use ibc_proto::{
 };
 use utils::encode::protobuf;
 
-use crate::{error::Error, config::CosmosChainConfig, account::Secp256k1Account};
+use crate::{error::Error, config::CosmosChainConfig, account::Secp256k1Account, keyring::KeyType};
 
 use super::types::{AccountSequence, Memo, GasConfig};
 
@@ -18,9 +18,10 @@ pub fn create_and_sign_tx(
     tx_memo: &Memo,
     messages: &[Any],
 ) -> Result<(Tx, TxRaw), Error> {
-    let public_key_bytes = account_info.key_pair()?.public_key_bytes()?;
+    let key_pair = account_info.key_pair()?;
+    let public_key_bytes = key_pair.public_key_bytes()?;
 
-    let signer_info = cosmos_signer_info(account_detail.sequence, public_key_bytes);
+    let signer_info = cosmos_signer_info(account_detail.sequence, public_key_bytes, key_pair.key_type);
 
     let tx_body = tx_body(messages, tx_memo, vec![]);
     let tx_body_bytes = tx_body_bytes(&tx_body)?;
@@ -72,9 +73,9 @@ pub fn tx_body_bytes(tx_body: &TxBody) -> Result<Vec<u8>, Error> {
     protobuf::encode_to_bytes(tx_body).map_err(|e| Error::utils_protobuf_encode("tx body".to_string(), e))
 }
 
-pub fn cosmos_signer_info(account_sequence: u64, key_bytes: Vec<u8>) -> SignerInfo {
+pub fn cosmos_signer_info(account_sequence: u64, key_bytes: Vec<u8>, key_type: KeyType) -> SignerInfo {
     let public_key = Any {
-        type_url: "/cosmos.crypto.secp256k1.PubKey".to_string(),
+        type_url: key_type.public_key_type_url().to_string(),
         value: key_bytes,
     };
 
@@ -11,8 +11,9 @@ use ibc_proto::{
     },
     google::protobuf::Any,
 };
-use log::{error, info};
+use tracing::{error, info};
 use serde::{Deserialize, Serialize};
+use tendermint::abci::Event;
 use tonic::transport::Channel;
 use utils::{
     encode::protobuf,
@@ -135,7 +136,7 @@ pub fn gas_to_fee(gas_config: &GasConfig, gas_amount: u64) -> Fee {
     Fee {
         amount: vec![coin_fee],
         gas_limit: adjusted_gas_limit,
-        payer: "".to_string(),
+        payer: gas_config.fee_payer.clone(),
         granter: gas_config.fee_granter.clone(),
     }
 }
@@ -207,6 +208,36 @@ pub async fn simulate_tx_with_grpc_address(
     Ok(response)
 }
 
+/// Gas and events reported by simulating a tx, without broadcasting it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulateResult {
+    pub gas_used: u64,
+    pub gas_wanted: u64,
+    pub events: Vec<Event>,
+}
+
+impl TryFrom<SimulateResponse> for SimulateResult {
+    type Error = Error;
+
+    fn try_from(response: SimulateResponse) -> Result<Self, Self::Error> {
+        let gas_info = response.gas_info.ok_or_else(Error::simulate_tx_gas)?;
+
+        let events = response
+            .result
+            .map(|result| result.events)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|event| Event::try_from(event).map_err(Error::simulate_event_decode))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(SimulateResult {
+            gas_used: gas_info.gas_used,
+            gas_wanted: gas_info.gas_wanted,
+            events,
+        })
+    }
+}
+
 // The transaction is simulated by the given service client
 pub async fn simulate_tx(
     grpc_service_client: &mut ServiceClient<Channel>,
@@ -228,3 +259,116 @@ pub async fn simulate_tx(
 
     Ok(response)
 }
+
+#[cfg(test)]
+pub mod estimate_tests {
+    use ibc_proto::cosmos::{
+        base::abci::v1beta1::{GasInfo, Result as AbciResult},
+        tx::v1beta1::{Fee, SimulateResponse},
+    };
+    use tendermint::abci::Event;
+
+    use crate::tx::types::{GasConfig, GasPrice};
+
+    use super::{calculate_fee, gas_to_fee, SimulateResult};
+
+    fn gas_config(gas_multiplier: f64, price: f64) -> GasConfig {
+        GasConfig {
+            default_gas: 100_000,
+            max_gas: 400_000,
+            gas_multiplier,
+            gas_price: GasPrice {
+                price,
+                denom: "stake".to_string(),
+            },
+            max_fee: Fee::default(),
+            fee_granter: "".to_string(),
+            fee_payer: "".to_string(),
+        }
+    }
+
+    #[test]
+    pub fn gas_to_fee_rounds_up_works() {
+        let fee = gas_to_fee(&gas_config(1.5, 0.5), 100_001);
+
+        // 100_001 * 1.5 = 150_001.5 is floored, 150_001 * 0.5 = 75_000.5 is ceiled
+        assert_eq!(fee.gas_limit, 150_001);
+        assert_eq!(fee.amount[0].amount, "75001");
+        assert_eq!(fee.amount[0].denom, "stake");
+    }
+
+    #[test]
+    pub fn gas_to_fee_is_bounded_by_max_gas_works() {
+        let fee = gas_to_fee(&gas_config(1.5, 2.0), 1_000_000);
+
+        assert_eq!(fee.gas_limit, 400_000);
+        assert_eq!(fee.amount[0].amount, "800000");
+    }
+
+    #[test]
+    pub fn gas_to_fee_without_multiplier_works() {
+        let fee = gas_to_fee(&gas_config(1.0, 0.25), 1_000);
+        assert_eq!(fee.gas_limit, 1_000);
+        assert_eq!(fee.amount[0].amount, "250");
+
+        let fee = gas_to_fee(&gas_config(1.5, 0.25), 0);
+        assert_eq!(fee.gas_limit, 0);
+        assert_eq!(fee.amount[0].amount, "0");
+    }
+
+    #[test]
+    pub fn gas_to_fee_with_granter_and_payer_works() {
+        let fee = gas_to_fee(&gas_config(1.0, 1.0), 1_000);
+        assert_eq!(fee.granter, "");
+        assert_eq!(fee.payer, "");
+
+        let mut config = gas_config(1.0, 1.0);
+        config.fee_granter = "cosmos1granter".to_string();
+        config.fee_payer = "cosmos1payer".to_string();
+
+        let fee = gas_to_fee(&config, 1_000);
+        assert_eq!(fee.granter, "cosmos1granter");
+        assert_eq!(fee.payer, "cosmos1payer");
+    }
+
+    #[test]
+    pub fn calculate_fee_works() {
+        let gas_price = GasPrice {
+            price: 0.5,
+            denom: "stake".to_string(),
+        };
+
+        assert_eq!(calculate_fee(3, &gas_price).amount, "2");
+        assert_eq!(calculate_fee(4, &gas_price).amount, "2");
+    }
+
+    #[test]
+    pub fn simulate_result_works() {
+        let event = Event::new(
+            "message",
+            [("action", "/ibc.core.channel.v1.MsgRecvPacket"), ("module", "ibc_channel")],
+        );
+
+        let response = SimulateResponse {
+            gas_info: Some(GasInfo {
+                gas_wanted: 200_000,
+                gas_used: 123_456,
+            }),
+            result: Some(AbciResult {
+                events: vec![event.clone().into()],
+                ..Default::default()
+            }),
+        };
+
+        let result = SimulateResult::try_from(response).unwrap();
+        assert_eq!(result.gas_used, 123_456);
+        assert_eq!(result.gas_wanted, 200_000);
+        assert_eq!(result.events, vec![event]);
+
+        let response = SimulateResponse {
+            gas_info: None,
+            result: None,
+        };
+        assert!(SimulateResult::try_from(response).is_err());
+    }
+}
@@ -1,9 +1,35 @@
+use std::{future::Future, time::Duration};
+
+use tendermint::Hash;
 use tendermint_rpc::{
     endpoint::broadcast::tx_async::Response as TxAsyncResponse,
-    endpoint::broadcast::tx_sync::Response as TxSyncResponse, Client, HttpClient,
+    endpoint::broadcast::tx_sync::Response as TxSyncResponse,
+    endpoint::tx::Response as TxResponse,
+    error::{Error as TrpcError, ErrorDetail as TrpcErrorDetail},
+    Client, HttpClient,
 };
+use tracing::debug;
+
+use crate::{config::CosmosChainConfig, error::Error, retry::poll_until, tx::types::TxFailure};
 
-use crate::error::Error;
+pub const DEFAULT_TX_CONFIRMATION_TIMEOUT: u64 = 30;
+pub const DEFAULT_TX_CONFIRMATION_POLL_INTERVAL: u64 = 500;
+
+pub fn tx_confirmation_timeout_from_config(config: &CosmosChainConfig) -> Duration {
+    Duration::from_secs(
+        config
+            .tx_confirmation_timeout
+            .unwrap_or(DEFAULT_TX_CONFIRMATION_TIMEOUT),
+    )
+}
+
+pub fn tx_confirmation_poll_interval_from_config(config: &CosmosChainConfig) -> Duration {
+    Duration::from_millis(
+        config
+            .tx_confirmation_poll_interval
+            .unwrap_or(DEFAULT_TX_CONFIRMATION_POLL_INTERVAL),
+    )
+}
 
 pub async fn broadcast_tx_sync(
     trpc_client: &HttpClient,
@@ -26,3 +52,267 @@ pub async fn broadcast_tx_async(
         .map_err(|e| Error::trpc("broadcast tx async".to_string(), e))?;
     Ok(response)
 }
+
+// `/tx?hash=` answers with an error until the tx is included in a block,
+// so a "not found" error is reported as `None` rather than a failure.
+pub async fn query_tx_by_hash(
+    trpc_client: &HttpClient,
+    tx_hash: Hash,
+) -> Result<Option<TxResponse>, Error> {
+    tx_query_result(trpc_client.tx(tx_hash, false).await)
+}
+
+pub fn tx_query_result<T>(result: Result<T, TrpcError>) -> Result<Option<T>, Error> {
+    match result {
+        Ok(response) => Ok(Some(response)),
+        Err(e) if is_tx_not_found(&e) => Ok(None),
+        Err(e) => Err(Error::trpc("query tx by hash".to_string(), e)),
+    }
+}
+
+// The node reports an unknown tx as a JSON-RPC error whose data reads `tx (<hash>) not found`,
+// the error's own message is only "response error"
+fn is_tx_not_found(e: &TrpcError) -> bool {
+    match e.detail() {
+        TrpcErrorDetail::Response(sub) => sub
+            .source
+            .data()
+            .is_some_and(|data| data.contains("not found")),
+        _ => false,
+    }
+}
+
+/// Broadcast the tx in sync mode, so it has passed `CheckTx`, then poll
+/// until it is committed or `timeout` expires. A tx that fails either
+/// `CheckTx` or `DeliverTx` is reported with its classified `TxFailure`.
+pub async fn broadcast_tx_and_wait_commit(
+    trpc_client: &HttpClient,
+    tx_bytes: Vec<u8>,
+    timeout: Duration,
+    poll_interval: Duration,
+) -> Result<TxResponse, Error> {
+    let response = broadcast_tx_sync(trpc_client, tx_bytes).await?;
+    if let Some(failure) = check_tx_failure(&response) {
+        return Err(Error::check_tx(response.hash.to_string(), failure));
+    }
+
+    let committed = wait_for_tx_commit_with(&response.hash.to_string(), timeout, poll_interval, || {
+        query_tx_by_hash(trpc_client, response.hash)
+    })
+    .await?;
+
+    if let Some(failure) = deliver_tx_failure(&committed) {
+        return Err(Error::deliver_tx(committed.hash.to_string(), failure));
+    }
+
+    Ok(committed)
+}
+
+// The sync broadcast response carries no codespace,
+// so a CheckTx failure is classified from its log
+pub fn check_tx_failure(response: &TxSyncResponse) -> Option<TxFailure> {
+    if response.code.is_ok() {
+        return None;
+    }
+
+    Some(TxFailure::new(
+        response.code.value(),
+        String::new(),
+        response.log.clone(),
+    ))
+}
+
+pub fn deliver_tx_failure(response: &TxResponse) -> Option<TxFailure> {
+    let tx_result = &response.tx_result;
+    if tx_result.code.is_ok() {
+        return None;
+    }
+
+    Some(TxFailure::new(
+        tx_result.code.value(),
+        tx_result.codespace.clone(),
+        tx_result.log.clone(),
+    ))
+}
+
+// Wait until `query_tx` returns the committed tx.
+pub async fn wait_for_tx_commit_with<T, F, Fut>(
+    tx_hash: &str,
+    timeout: Duration,
+    poll_interval: Duration,
+    query_tx: F,
+) -> Result<T, Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Option<T>, Error>>,
+{
+    debug!("waiting for tx {} to be committed", tx_hash);
+    poll_until(timeout, poll_interval, query_tx)
+        .await
+        .ok_or_else(|| Error::tx_confirmation_timeout(tx_hash.to_string(), timeout))
+}
+
+#[cfg(test)]
+pub mod send_tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use tendermint::{abci::Code, block::Height, Hash};
+    use tendermint_rpc::{
+        endpoint::{broadcast::tx_sync::Response as TxSyncResponse, tx::Response as TxResponse},
+        error::Error as TrpcError,
+        Code as RpcCode, ResponseError,
+    };
+
+    use crate::{error::ErrorDetail, tx::types::TxFailureKind};
+
+    use super::{check_tx_failure, deliver_tx_failure, tx_query_result, wait_for_tx_commit_with};
+
+    fn tx_sync_response(code: u32, log: &str) -> TxSyncResponse {
+        TxSyncResponse {
+            code: Code::from(code),
+            data: Default::default(),
+            log: log.to_string(),
+            hash: Hash::None,
+        }
+    }
+
+    fn tx_response(code: u32, codespace: &str, log: &str) -> TxResponse {
+        let mut response = TxResponse {
+            hash: Hash::None,
+            height: Height::from(10_u32),
+            index: 0,
+            tx_result: Default::default(),
+            tx: vec![],
+            proof: None,
+        };
+        response.tx_result.code = Code::from(code);
+        response.tx_result.codespace = codespace.to_string();
+        response.tx_result.log = log.to_string();
+        response
+    }
+
+    #[test]
+    pub fn check_tx_failure_works() {
+        assert!(check_tx_failure(&tx_sync_response(0, "")).is_none());
+
+        let failure = check_tx_failure(&tx_sync_response(
+            32,
+            "account sequence mismatch, expected 10, got 9: incorrect account sequence",
+        ))
+        .unwrap();
+        assert_eq!(failure.code, 32);
+        assert_eq!(failure.codespace, "");
+        assert_eq!(failure.kind, TxFailureKind::SequenceMismatch);
+    }
+
+    #[test]
+    pub fn deliver_tx_failure_works() {
+        assert!(deliver_tx_failure(&tx_response(0, "", "")).is_none());
+
+        let failure = deliver_tx_failure(&tx_response(
+            11,
+            "sdk",
+            "out of gas in location: WriteFlat; gasWanted: 100, gasUsed: 120: out of gas",
+        ))
+        .unwrap();
+        assert_eq!(failure.code, 11);
+        assert_eq!(failure.codespace, "sdk");
+        assert_eq!(failure.kind, TxFailureKind::OutOfGas);
+    }
+
+    #[actix_rt::test]
+    pub async fn wait_for_tx_commit_works() {
+        let polls = Arc::new(Mutex::new(0_u32));
+
+        let committed_height = wait_for_tx_commit_with(
+            "mock_hash",
+            Duration::from_secs(5),
+            Duration::from_millis(1),
+            || {
+                let polls = polls.clone();
+                async move {
+                    let mut polls = polls.lock().unwrap();
+                    *polls += 1;
+                    if *polls <= 3 {
+                        Ok(None)
+                    } else {
+                        Ok(Some(100_u64))
+                    }
+                }
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(committed_height, 100);
+        assert_eq!(*polls.lock().unwrap(), 4);
+    }
+
+    fn rpc_response_error(data: &str) -> TrpcError {
+        TrpcError::response(ResponseError::new(RpcCode::InternalError, Some(data.to_string())))
+    }
+
+    #[test]
+    pub fn tx_query_result_works() {
+        let result = tx_query_result::<TxResponse>(Err(rpc_response_error(
+            "tx (6C34B6B1A9A1F4E1B2B2B1D0C0A1A2A3A4A5A6A7A8A9AAABACADAEAFB0B1B2B3) not found",
+        )));
+        assert!(result.unwrap().is_none());
+
+        let result = tx_query_result::<TxResponse>(Err(rpc_response_error("internal error")));
+        match result {
+            Err(e) => assert!(matches!(e.detail(), ErrorDetail::Trpc(_))),
+            Ok(_) => panic!("only a not found error means the tx is not committed yet"),
+        }
+
+        let result = tx_query_result(Ok(tx_response(0, "", ""))).unwrap();
+        assert_eq!(result.unwrap().height, Height::from(10_u32));
+    }
+
+    #[actix_rt::test]
+    pub async fn wait_for_not_found_tx_commit_works() {
+        let polls = Arc::new(Mutex::new(0_u32));
+
+        let committed = wait_for_tx_commit_with(
+            "mock_hash",
+            Duration::from_secs(5),
+            Duration::from_millis(1),
+            || {
+                let polls = polls.clone();
+                async move {
+                    let mut polls = polls.lock().unwrap();
+                    *polls += 1;
+                    if *polls <= 2 {
+                        tx_query_result(Err(rpc_response_error("tx (mock_hash) not found")))
+                    } else {
+                        tx_query_result(Ok(tx_response(0, "", "")))
+                    }
+                }
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(committed.height, Height::from(10_u32));
+        assert_eq!(*polls.lock().unwrap(), 3);
+    }
+
+    #[actix_rt::test]
+    pub async fn wait_for_tx_commit_timeout_works() {
+        let result = wait_for_tx_commit_with(
+            "mock_hash",
+            Duration::from_millis(20),
+            Duration::from_millis(5),
+            || async { Ok(None::<u64>) },
+        )
+        .await;
+
+        match result {
+            Err(e) => assert!(matches!(e.detail(), ErrorDetail::TxConfirmationTimeout(_))),
+            Ok(_) => panic!("tx should not be committed"),
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use std::fmt::{Display, Formatter, Error as FmtError};
+use std::{
+    fmt::{Display, Formatter, Error as FmtError},
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use ibc_proto::cosmos::{auth::v1beta1::BaseAccount, tx::v1beta1::Fee};
 use serde::{Deserialize, Serialize, Deserializer, Serializer, de::Error as _};
@@ -11,6 +14,10 @@ use super::estimate::calculate_fee;
 const DEFAULT_MAX_GAS: u64 = 400_000;
 
 const DEFAULT_FEE_GRANTER: &str = "";
+const DEFAULT_FEE_PAYER: &str = "";
+
+/// Default multiplier applied to the simulated gas amount.
+const DEFAULT_GAS_MULTIPLIER: f64 = 1.1;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GasPrice {
@@ -26,6 +33,7 @@ pub struct GasConfig {
     pub gas_price: GasPrice,
     pub max_fee: Fee,
     pub fee_granter: String,
+    pub fee_payer: String,
 }
 
 impl<'a> From<&'a CosmosChainConfig> for GasConfig {
@@ -37,6 +45,7 @@ impl<'a> From<&'a CosmosChainConfig> for GasConfig {
             gas_price: config.gas_price.clone(),
             max_fee: max_fee_from_config(config),
             fee_granter: fee_granter_from_config(config),
+            fee_payer: fee_payer_from_config(config),
         }
     }
 }
@@ -56,7 +65,7 @@ pub fn max_gas_from_config(config: &CosmosChainConfig) -> u64 {
 
 /// The gas multiplier
 pub fn gas_multiplier_from_config(config: &CosmosChainConfig) -> f64 {
-    config.gas_multiplier.unwrap_or_default()
+    config.gas_multiplier.unwrap_or(DEFAULT_GAS_MULTIPLIER)
 }
 
 /// Get the fee granter address
@@ -68,6 +77,15 @@ fn fee_granter_from_config(config: &CosmosChainConfig) -> String {
         .to_string()
 }
 
+/// Get the fee payer address, which pays the fee instead of the first signer
+fn fee_payer_from_config(config: &CosmosChainConfig) -> String {
+    config
+        .fee_payer
+        .as_deref()
+        .unwrap_or(DEFAULT_FEE_PAYER)
+        .to_string()
+}
+
 fn max_fee_from_config(config: &CosmosChainConfig) -> Fee {
     let max_gas = max_gas_from_config(config);
 
@@ -75,11 +93,12 @@ fn max_fee_from_config(config: &CosmosChainConfig) -> Fee {
     let max_fee_in_coins = calculate_fee(max_gas, &config.gas_price);
 
     let fee_granter = fee_granter_from_config(config);
+    let fee_payer = fee_payer_from_config(config);
 
     Fee {
         amount: vec![max_fee_in_coins],
         gas_limit: max_gas,
-        payer: "".to_string(),
+        payer: fee_payer,
         granter: fee_granter,
     }
 }
@@ -112,6 +131,53 @@ impl Memo {
     pub fn as_str(&self) -> &str {
         &self.0
     }
+
+    /// Build a memo from a template, resolving the `{chain_id}`,
+    /// `{batch_size}` and `{timestamp}` placeholders.
+    pub fn from_template(template: &str, variables: &MemoVariables<'_>) -> Result<Self, MemoError> {
+        let memo = template
+            .replace("{chain_id}", variables.chain_id)
+            .replace("{batch_size}", &variables.batch_size.to_string())
+            .replace("{timestamp}", &variables.timestamp.to_string());
+
+        Self::new(memo)
+    }
+}
+
+/// Run-time values substituted into a memo template.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemoVariables<'a> {
+    pub chain_id: &'a str,
+    pub batch_size: usize,
+    pub timestamp: u64,
+}
+
+/// Build the memo of a batch of `batch_size` messages: the configured prefix
+/// followed by the resolved template, if any.
+pub fn memo_from_config(config: &CosmosChainConfig, batch_size: usize) -> Result<Memo, MemoError> {
+    let mut memo = Memo::new(config.memo_prefix.clone().unwrap_or_default())?;
+
+    if let Some(template) = config.memo_template.as_deref() {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let variables = MemoVariables {
+            chain_id: &config.chain_id,
+            batch_size,
+            timestamp,
+        };
+
+        let suffix = Memo::from_template(template, &variables)?;
+        if !suffix.as_str().is_empty() {
+            memo.apply_suffix(suffix.as_str());
+        }
+        if memo.as_str().len() > MEMO_MAX_LEN {
+            return Err(MemoError::too_long(memo.as_str().len()));
+        }
+    }
+
+    Ok(memo)
 }
 
 impl<'de> Deserialize<'de> for Memo {
@@ -238,5 +304,207 @@ impl Display for AccountSequence {
     }
 }
 
+/// What went wrong with a tx rejected in `CheckTx` or failed in `DeliverTx`,
+/// as far as retry logic needs to know.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TxFailureKind {
+    OutOfGas,
+    InsufficientFee,
+    InsufficientFunds,
+    SequenceMismatch,
+    MempoolFull,
+    TxInMempoolCache,
+    // The client is expired or frozen and must be updated or recovered first
+    ClientNotActive,
+    Unknown,
+}
+
+impl TxFailureKind {
+    pub fn classify(codespace: &str, code: u32) -> Self {
+        match (codespace, code) {
+            ("sdk", 5) => Self::InsufficientFunds,
+            ("sdk", 11) => Self::OutOfGas,
+            ("sdk", 13) => Self::InsufficientFee,
+            ("sdk", 19) => Self::TxInMempoolCache,
+            ("sdk", 20) => Self::MempoolFull,
+            ("sdk", 32) => Self::SequenceMismatch,
+            ("client", 29) => Self::ClientNotActive,
+            _ => Self::Unknown,
+        }
+    }
+
+    // Without a codespace the code is ambiguous, fall back to the
+    // messages of the registered errors wrapped in the raw log
+    pub fn classify_log(raw_log: &str) -> Self {
+        if raw_log.contains("out of gas") {
+            Self::OutOfGas
+        } else if raw_log.contains("insufficient fee") {
+            Self::InsufficientFee
+        } else if raw_log.contains("insufficient funds") {
+            Self::InsufficientFunds
+        } else if raw_log.contains("incorrect account sequence") {
+            Self::SequenceMismatch
+        } else if raw_log.contains("mempool is full") {
+            Self::MempoolFull
+        } else if raw_log.contains("tx already in mempool") {
+            Self::TxInMempoolCache
+        } else if raw_log.contains("client state is not active") {
+            Self::ClientNotActive
+        } else {
+            Self::Unknown
+        }
+    }
+}
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TxFailure {
+    pub code: u32,
+    pub codespace: String,
+    pub raw_log: String,
+    pub kind: TxFailureKind,
+}
 
+impl TxFailure {
+    pub fn new(code: u32, codespace: String, raw_log: String) -> Self {
+        let kind = if codespace.is_empty() {
+            TxFailureKind::classify_log(&raw_log)
+        } else {
+            TxFailureKind::classify(&codespace, code)
+        };
+        Self {
+            code,
+            codespace,
+            raw_log,
+            kind,
+        }
+    }
+}
+
+impl Display for TxFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        write!(
+            f,
+            "{:?} (codespace: {}, code: {}): {}",
+            self.kind, self.codespace, self.code, self.raw_log
+        )
+    }
+}
+
+#[cfg(test)]
+pub mod types_tests {
+    use crate::config::{cosmos_config_test::test_chain_config, CosmosChainConfig};
+
+    use super::{memo_from_config, Memo, MemoVariables, TxFailure, TxFailureKind};
+
+    fn memo_config(memo_prefix: Option<&str>, memo_template: Option<&str>) -> CosmosChainConfig {
+        let mut config = test_chain_config();
+        config.memo_prefix = memo_prefix.map(str::to_string);
+        config.memo_template = memo_template.map(str::to_string);
+        config
+    }
+
+    #[test]
+    pub fn memo_template_works() {
+        let variables = MemoVariables {
+            chain_id: "chain-a",
+            batch_size: 12,
+            timestamp: 1690000000,
+        };
+
+        let memo = Memo::from_template("{chain_id}/{batch_size}@{timestamp}", &variables).unwrap();
+        assert_eq!(memo.as_str(), "chain-a/12@1690000000");
+
+        let memo = Memo::from_template("relayer-1 {chain_id} {chain_id}", &variables).unwrap();
+        assert_eq!(memo.as_str(), "relayer-1 chain-a chain-a");
+    }
+
+    #[test]
+    pub fn empty_memo_template_works() {
+        let variables = MemoVariables {
+            chain_id: "chain-a",
+            batch_size: 1,
+            timestamp: 0,
+        };
+
+        let memo = Memo::from_template("", &variables).unwrap();
+        assert_eq!(memo, Memo::default());
+    }
+
+    #[test]
+    pub fn memo_from_config_works() {
+        let memo = memo_from_config(&memo_config(None, None), 3).unwrap();
+        assert_eq!(memo, Memo::default());
+
+        let memo = memo_from_config(&memo_config(Some("relayer-1"), None), 3).unwrap();
+        assert_eq!(memo.as_str(), "relayer-1");
+
+        let memo = memo_from_config(&memo_config(Some("relayer-1"), Some("{chain_id}/{batch_size}")), 3).unwrap();
+        assert_eq!(memo.as_str(), "relayer-1 | test_chain/3");
+
+        let memo = memo_from_config(&memo_config(None, Some("{chain_id}/{batch_size}")), 3).unwrap();
+        assert_eq!(memo.as_str(), "test_chain/3");
+    }
+
+    #[test]
+    pub fn too_long_memo_from_config_fails() {
+        // Prefix and template both fit on their own, joined they do not
+        let prefix = "a-relayer-memo-prefix-of-30-ch";
+        let template = "{chain_id} batch of {batch_size} messages";
+        assert!(Memo::new(prefix).is_ok());
+
+        let config = memo_config(Some(prefix), Some(template));
+        assert!(memo_from_config(&config, 10).is_err());
+    }
+
+    #[test]
+    pub fn too_long_memo_template_fails() {
+        let variables = MemoVariables {
+            chain_id: "a-very-long-chain-identifier-used-for-testing",
+            batch_size: 1000,
+            timestamp: 1690000000,
+        };
+
+        assert!(Memo::from_template("{chain_id}-{batch_size}-{timestamp}", &variables).is_err());
+    }
+
+    #[test]
+    pub fn tx_failure_kind_works() {
+        let failure = TxFailure::new(11, "sdk".to_string(), "out of gas in location: ReadFlat".to_string());
+        assert_eq!(failure.kind, TxFailureKind::OutOfGas);
+
+        let failure = TxFailure::new(
+            32,
+            "sdk".to_string(),
+            "account sequence mismatch, expected 10, got 9: incorrect account sequence".to_string(),
+        );
+        assert_eq!(failure.kind, TxFailureKind::SequenceMismatch);
+
+        assert_eq!(TxFailureKind::classify("sdk", 13), TxFailureKind::InsufficientFee);
+        assert_eq!(TxFailureKind::classify("sdk", 5), TxFailureKind::InsufficientFunds);
+        assert_eq!(TxFailureKind::classify("client", 29), TxFailureKind::ClientNotActive);
+
+        // The same code means something else in another codespace
+        assert_eq!(TxFailureKind::classify("channel", 11), TxFailureKind::Unknown);
+        assert_eq!(TxFailureKind::classify("", 0), TxFailureKind::Unknown);
+    }
+
+    #[test]
+    pub fn tx_failure_kind_from_log_works() {
+        let failure = TxFailure::new(
+            32,
+            "".to_string(),
+            "account sequence mismatch, expected 10, got 9: incorrect account sequence".to_string(),
+        );
+        assert_eq!(failure.kind, TxFailureKind::SequenceMismatch);
+
+        let failure = TxFailure::new(
+            13,
+            "".to_string(),
+            "insufficient fees; got: 10stake required: 20stake: insufficient fee".to_string(),
+        );
+        assert_eq!(failure.kind, TxFailureKind::InsufficientFee);
+
+        let failure = TxFailure::new(1, "".to_string(), "internal error".to_string());
+        assert_eq!(failure.kind, TxFailureKind::Unknown);
+    }
+}
@@ -9,9 +9,8 @@ use http::Uri;
 
 use cli::{client::Client, cmd::rootcmd::CMD};
 use cosmos_chain::{query::grpc::account::query_detail_account, chain::CosmosChain, account::Secp256k1Account};
-use log::info;
 use tokio::sync::mpsc;
-use tracing::{info_span, metadata::LevelFilter};
+use tracing::{info, info_span, metadata::LevelFilter};
 use tracing_subscriber::EnvFilter;
 
 #[tokio::main]
@@ -32,13 +31,15 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     // env_logger::init();
     
-    tracing_subscriber::fmt().with_max_level(tracing::Level::INFO).init();
+    // Log level and per-module filters come from RUST_LOG, e.g. RUST_LOG=cosmos_chain=trace
+    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    tracing_subscriber::fmt().with_env_filter(env_filter).init();
     
     // let span = info_span!("main");
     let file_path = "/Users/joten/rust_projects/TxAggregator/cosmos_chain/src/config/chain_config.toml";
-    let mut cosmos_chain = CosmosChain::new(file_path);
+    let mut cosmos_chain = CosmosChain::try_new(file_path)?;
 
-    let account = Secp256k1Account::new(&cosmos_chain.config.chain_a_key_path, &cosmos_chain.config.hd_path)?;
+    let account = Secp256k1Account::from_config(&cosmos_chain.config)?;
 
     
     // let _span = span.enter();